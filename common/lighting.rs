@@ -0,0 +1,188 @@
+//! The lighting rig, shared by the `cubes` and `yt` binaries.
+
+use amethyst::{
+    core::{math::Vector3, Transform},
+    ecs::prelude::World,
+    prelude::Builder,
+    renderer::{
+        light,
+        palette::{Srgb, Srgba},
+        resources::AmbientColor,
+    },
+};
+
+use serde::{Deserialize, Serialize};
+
+/// A light of the lighting rig. Colors are in sRGB, positions in world space.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum LightDesc {
+    Directional {
+        #[serde(default = "default_light_color")]
+        color: [f32; 3],
+        direction: [f32; 3],
+        intensity: f32,
+    },
+    Point {
+        #[serde(default = "default_light_color")]
+        color: [f32; 3],
+        position: [f32; 3],
+        intensity: f32,
+        radius: f32,
+        #[serde(default = "default_smoothness")]
+        smoothness: f32,
+    },
+    Spot {
+        #[serde(default = "default_light_color")]
+        color: [f32; 3],
+        position: [f32; 3],
+        direction: [f32; 3],
+        intensity: f32,
+        /// Distance reached by the light.
+        range: f32,
+        /// Opening of the cone, in degrees.
+        angle: f32,
+        #[serde(default = "default_smoothness")]
+        smoothness: f32,
+    },
+}
+
+fn default_light_color() -> [f32; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+fn default_smoothness() -> f32 {
+    1.0
+}
+
+/// The lights of a level or scene, together with the ambient color.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LightingDesc {
+    #[serde(default)]
+    pub ambient_color: [f32; 3],
+    #[serde(default)]
+    pub lights: Vec<LightDesc>,
+}
+
+impl Default for LightingDesc {
+    /// The single directional light used before the rig was configurable.
+    fn default() -> Self {
+        LightingDesc {
+            ambient_color: [0.0, 0.0, 0.0],
+            lights: vec![LightDesc::Directional {
+                color: default_light_color(),
+                direction: [-1.0, -1.0, -1.0],
+                intensity: 5.0,
+            }],
+        }
+    }
+}
+
+/// Sets the `AmbientColor` and creates an entity for each light.
+pub fn add_lights(world: &mut World, lighting: &LightingDesc) {
+    let [r, g, b] = lighting.ambient_color;
+    world.add_resource(AmbientColor(Srgba::new(r, g, b, 1.0)));
+
+    for desc in lighting.lights.iter() {
+        let mut transform = Transform::default();
+
+        let light: light::Light = match desc {
+            LightDesc::Directional {
+                color,
+                direction,
+                intensity,
+            } => light::DirectionalLight {
+                color: srgb(color),
+                direction: vector(direction).normalize(),
+                intensity: *intensity,
+            }
+            .into(),
+            LightDesc::Point {
+                color,
+                position,
+                intensity,
+                radius,
+                smoothness,
+            } => {
+                transform.set_translation(vector(position));
+                light::PointLight {
+                    color: srgb(color),
+                    intensity: *intensity,
+                    radius: *radius,
+                    smoothness: *smoothness,
+                }
+                .into()
+            }
+            LightDesc::Spot {
+                color,
+                position,
+                direction,
+                intensity,
+                range,
+                angle,
+                smoothness,
+            } => {
+                transform.set_translation(vector(position));
+                light::SpotLight {
+                    angle: angle.to_radians(),
+                    color: srgb(color),
+                    direction: vector(direction).normalize(),
+                    intensity: *intensity,
+                    range: *range,
+                    smoothness: *smoothness,
+                }
+                .into()
+            }
+        };
+
+        world.create_entity().with(light).with(transform).build();
+    }
+}
+
+fn srgb(color: &[f32; 3]) -> Srgb {
+    Srgb::new(color[0], color[1], color[2])
+}
+
+fn vector(v: &[f32; 3]) -> Vector3<f32> {
+    Vector3::new(v[0], v[1], v[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lighting_round_trips() {
+        let lighting: LightingDesc = ron::de::from_str(
+            "(
+                ambient_color: (0.1, 0.2, 0.3),
+                lights: [
+                    Directional(direction: (0.0, -1.0, 0.0), intensity: 2.0),
+                    Point(color: (1.0, 0.5, 0.0), position: (1.0, 2.0, 3.0), intensity: 10.0, radius: 5.0),
+                    Spot(position: (0.0, 4.0, 0.0), direction: (0.0, -1.0, 0.0), intensity: 8.0, range: 6.0, angle: 30.0),
+                ],
+            )",
+        )
+        .unwrap();
+
+        let data = ron::ser::to_string(&lighting).unwrap();
+        let reloaded: LightingDesc = ron::de::from_str(&data).unwrap();
+        assert_eq!(format!("{:?}", lighting), format!("{:?}", reloaded));
+
+        assert_eq!(reloaded.ambient_color, [0.1, 0.2, 0.3]);
+        match &reloaded.lights[0] {
+            LightDesc::Directional { color, .. } => assert_eq!(*color, default_light_color()),
+            other => panic!("Expected a directional light, got {:?}", other),
+        }
+        match &reloaded.lights[2] {
+            LightDesc::Spot { smoothness, .. } => assert_eq!(*smoothness, default_smoothness()),
+            other => panic!("Expected a spot light, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn empty_lighting_has_no_lights() {
+        let lighting: LightingDesc = ron::de::from_str("()").unwrap();
+        assert_eq!(lighting.ambient_color, [0.0, 0.0, 0.0]);
+        assert!(lighting.lights.is_empty());
+    }
+}
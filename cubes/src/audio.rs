@@ -0,0 +1,237 @@
+use crate::contact_event_system::{ContactEvent, ContactEventKind};
+
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    audio::{
+        output::{init_output, Output},
+        FlacFormat, OggFormat, Source, SourceHandle, WavFormat,
+    },
+    core::shrev::{EventChannel, ReaderId},
+    ecs::{Read, ReadExpect, Resources, System, SystemData, Write},
+};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+
+/// Audio settings, loaded from `configs/audio_conf.ron`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master_volume: f32,
+    /// Plays nothing, even when an audio device is available.
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        AudioSettings {
+            master_volume: 1.0,
+            muted: false,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn path() -> String {
+        crate::get_dir_path("/configs/audio_conf.ron")
+    }
+}
+
+/// A sound file, relative to the game directory, and the volume to play it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SoundDesc {
+    pub file: String,
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+}
+
+fn default_volume() -> f32 {
+    1.0
+}
+
+/// The impact volume grows with the contact impulse, from silent at `min_impulse`
+/// up to the sound volume at `max_impulse`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ImpactSoundDesc {
+    pub sound: SoundDesc,
+    pub min_impulse: f32,
+    pub max_impulse: f32,
+}
+
+/// Maps the game events to sounds, loaded from `configs/sounds_conf.ron`.
+/// Leave an event out to keep it silent.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundsConfig {
+    pub shot: Option<SoundDesc>,
+    pub impact: Option<ImpactSoundDesc>,
+    pub safe_zone_activated: Option<SoundDesc>,
+}
+
+impl SoundsConfig {
+    /// Every sound mapped by the config.
+    pub fn sounds(&self) -> impl Iterator<Item = &SoundDesc> {
+        self.shot
+            .iter()
+            .chain(self.impact.iter().map(|impact| &impact.sound))
+            .chain(self.safe_zone_activated.iter())
+    }
+}
+
+/// Game events that produce a sound. The impacts are taken from the `ContactEvent`s.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SoundEvent {
+    Shot,
+    SafeZoneActivated,
+}
+
+/// Where the sounds end up.
+///
+/// `Null` plays nothing, and it's used when there is no audio device, so the game
+/// and the headless scenarios run the same systems.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AudioBackend {
+    Device,
+    Null,
+}
+
+impl Default for AudioBackend {
+    fn default() -> Self {
+        AudioBackend::Device
+    }
+}
+
+/// Counts the sounds actually played on the audio device.
+#[derive(Default)]
+pub struct AudioStats {
+    pub played: usize,
+}
+
+/// Plays the sounds mapped in the `SoundsConfig`.
+///
+/// The sound files are loaded at setup, so they are ready the first time they are played.
+pub struct AudioSystem {
+    config: SoundsConfig,
+    sources: HashMap<String, SourceHandle>,
+    sound_reader: Option<ReaderId<SoundEvent>>,
+    contact_reader: Option<ReaderId<ContactEvent>>,
+}
+
+impl AudioSystem {
+    pub fn new(config: SoundsConfig) -> Self {
+        AudioSystem {
+            config,
+            sources: HashMap::new(),
+            sound_reader: None,
+            contact_reader: None,
+        }
+    }
+}
+
+impl<'s> System<'s> for AudioSystem {
+    type SystemData = (
+        Read<'s, EventChannel<SoundEvent>>,
+        Read<'s, EventChannel<ContactEvent>>,
+        Read<'s, AudioBackend>,
+        Read<'s, AudioSettings>,
+        ReadExpect<'s, Loader>,
+        Read<'s, AssetStorage<Source>>,
+        Option<Read<'s, Output>>,
+        Write<'s, AudioStats>,
+    );
+
+    fn run(
+        &mut self,
+        (sound_events, contacts, backend, settings, loader, storage, output, mut stats): Self::SystemData,
+    ) {
+        // (sound, volume)
+        let mut to_play = Vec::<(SoundDesc, f32)>::new();
+
+        for event in sound_events.read(self.sound_reader.as_mut().unwrap()) {
+            let sound = match event {
+                SoundEvent::Shot => &self.config.shot,
+                SoundEvent::SafeZoneActivated => &self.config.safe_zone_activated,
+            };
+            if let Some(sound) = sound {
+                to_play.push((sound.clone(), sound.volume));
+            }
+        }
+
+        for event in contacts.read(self.contact_reader.as_mut().unwrap()) {
+            if event.kind != ContactEventKind::Began {
+                continue;
+            }
+            // Both bodies report the contact, play it once.
+            if event.other.map_or(false, |other| other.id() < event.entity.id()) {
+                continue;
+            }
+            if let Some(impact) = &self.config.impact {
+                let range = (impact.max_impulse - impact.min_impulse).max(std::f32::EPSILON);
+                let strength = (event.impulse - impact.min_impulse) / range;
+                if strength > 0.0 {
+                    to_play.push((impact.sound.clone(), impact.sound.volume * strength.min(1.0)));
+                }
+            }
+        }
+
+        if settings.muted {
+            return;
+        }
+
+        for (sound, volume) in to_play {
+            let volume = volume * settings.master_volume;
+
+            match (*backend, output.as_ref()) {
+                (AudioBackend::Device, Some(output)) => {
+                    let handle = self
+                        .sources
+                        .entry(sound.file.clone())
+                        .or_insert_with(|| load_source(&loader, &storage, &sound.file));
+                    // Skipped while the file is still loading.
+                    if let Some(source) = storage.get(handle) {
+                        output.play_once(source, volume);
+                        stats.played += 1;
+                    }
+                }
+                _ => log::trace!("Sound {} at volume {:.2}", sound.file, volume),
+            }
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+
+        if *res.fetch::<AudioBackend>() == AudioBackend::Device {
+            init_output(res);
+            if !res.has_value::<Output>() {
+                log::warn!("No audio device found, the sounds are disabled.");
+                *res.fetch_mut::<AudioBackend>() = AudioBackend::Null;
+            }
+        }
+
+        if *res.fetch::<AudioBackend>() == AudioBackend::Device {
+            let loader = res.fetch::<Loader>();
+            let storage = res.fetch::<AssetStorage<Source>>();
+            for sound in self.config.sounds() {
+                if !self.sources.contains_key(&sound.file) {
+                    let handle = load_source(&loader, &storage, &sound.file);
+                    self.sources.insert(sound.file.clone(), handle);
+                }
+            }
+        }
+
+        self.sound_reader = Some(res.fetch_mut::<EventChannel<SoundEvent>>().register_reader());
+        self.contact_reader = Some(res.fetch_mut::<EventChannel<ContactEvent>>().register_reader());
+    }
+}
+
+fn load_source(loader: &Loader, storage: &AssetStorage<Source>, file: &str) -> SourceHandle {
+    let path = crate::get_dir_path(&format!("/{}", file));
+    let extension = file.rsplit('.').next().unwrap_or("").to_lowercase();
+    match extension.as_str() {
+        "ogg" => loader.load(path, OggFormat, (), storage),
+        "flac" => loader.load(path, FlacFormat, (), storage),
+        _ => loader.load(path, WavFormat, (), storage),
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{
+    components::*,
+    game_state::{create_rigid_body, InitialVelocity},
+    physics_material::PhysicsMaterial,
+    transform_utils::*,
+};
+
+use amethyst::{
+    amethyst_nphysics,
+    core::{
+        math::Vector3,
+        transform::TransformBundle,
+        frame_limiter::FrameRateLimitStrategy,
+        Transform,
+    },
+    ecs::{prelude::World, Entities, Entity, Join, ReadStorage, System, Write},
+    phythyst::{objects::*, servers::*, PhysicsBundle, PhysicsTime},
+    prelude::{Application, Builder, GameData, GameDataBuilder, SimpleState, SimpleTrans, StateData, Trans},
+};
+
+use std::collections::HashMap;
+
+/// Number of bullets fired against the platform.
+const BULLETS_TO_FIRE: usize = 200;
+
+/// Frames per second of the scenario, and of its physics.
+const FRAMES_PER_SECOND: u32 = 240;
+
+/// Frames to wait, after the last shot, before ending the scenario.
+const SETTLE_FRAMES: u32 = 3 * FRAMES_PER_SECOND;
+
+/// Counts the bullets that went through a platform.
+#[derive(Default)]
+pub struct TunnellingReport {
+    pub tunnelled: usize,
+}
+
+/// Fires fast bullets at a thin platform, without rendering, and returns the
+/// number of bullets that passed through it.
+fn run_scenario() -> amethyst::Result<usize> {
+    let game_data = GameDataBuilder::default()
+        .with_bundle(PhysicsBundle::new())?
+        .with_barrier()
+        .with(TunnellingCheckSystem::default(), "tunnelling_check_system", &[])
+        .with_bundle(TransformBundle::new())?;
+
+    let mut game = Application::build("./", CcdScenarioState::default())?
+        .with_frame_limit(FrameRateLimitStrategy::Sleep, FRAMES_PER_SECOND)
+        .with_physics(amethyst_nphysics::create_physics::<f32>())
+        .with_resource(PhysicsTime::default().set_frames_per_second(FRAMES_PER_SECOND))
+        .with_resource(TunnellingReport::default())
+        .build(game_data)?;
+
+    game.run();
+
+    let report = game.world().read_resource::<TunnellingReport>();
+    log::info!(
+        "CCD scenario: {} bullets fired, {} tunnelled through the platform.",
+        BULLETS_TO_FIRE,
+        report.tunnelled
+    );
+    Ok(report.tunnelled)
+}
+
+#[test]
+fn fast_bullets_do_not_tunnel() -> amethyst::Result<()> {
+    assert_eq!(run_scenario()?, 0, "Some bullets passed through the platform.");
+    Ok(())
+}
+
+#[derive(Default)]
+struct CcdScenarioState {
+    fired: usize,
+    settle_frames: u32,
+    bullet_shape: Option<PhysicsShapeTag>,
+}
+
+impl SimpleState for CcdScenarioState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        data.world.register::<Bullet>();
+
+        // Same size of the cubes platforms.
+        let platform_desc = ShapeDesc::Cube {
+            half_extents: Vector3::new(10.0, 10.0, 0.3),
+        };
+        let (platform_shape, bullet_shape) = {
+            let mut shape_server = data.world.write_resource::<ShapePhysicsServer<f32>>();
+            (
+                shape_server.create_shape(&platform_desc),
+                shape_server.create_shape(&ShapeDesc::Sphere { radius: 0.5 }),
+            )
+        };
+        self.bullet_shape = Some(bullet_shape);
+
+        // Horizontal platform, the thin side is along Y.
+        let mut transf = Transform::default();
+        transf.append_rotation_x_axis(90.0f32.to_radians());
+        let rb = create_rigid_body(
+            data.world,
+            &transf,
+            platform_shape,
+            BodyMode::Static,
+            &PhysicsMaterial::default(),
+            &InitialVelocity::default(),
+        );
+        data.world
+            .create_entity()
+            .with(transf)
+            .with(rb)
+            .with(ColliderShape::new(platform_desc))
+            .build();
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        if self.fired < BULLETS_TO_FIRE {
+            self.fire(data.world);
+            self.fired += 1;
+            Trans::None
+        } else {
+            self.settle_frames += 1;
+            if self.settle_frames < SETTLE_FRAMES {
+                Trans::None
+            } else {
+                Trans::Quit
+            }
+        }
+    }
+}
+
+impl CcdScenarioState {
+    fn fire(&self, world: &mut World) {
+        // Spread the shots across the platform, with growing speed.
+        let i = self.fired as f32;
+        let mut transf = Transform::default();
+        transf.set_translation_xyz((i * 0.37) % 16.0 - 8.0, 20.0, (i * 0.71) % 16.0 - 8.0);
+
+        let rb = create_rigid_body(
+            world,
+            &transf,
+            self.bullet_shape.unwrap(),
+            BodyMode::Dynamic,
+            &PhysicsMaterial {
+                ccd: true,
+                ..PhysicsMaterial::bouncy()
+            },
+            &InitialVelocity {
+                linear: Vector3::new(0.0, -100.0 - i * 5.0, 0.0),
+                ..Default::default()
+            },
+        );
+
+        world
+            .create_entity()
+            .with(transf)
+            .with(rb)
+            .with(Bullet::default())
+            .build();
+    }
+}
+
+/// Checks whether a bullet crossed the middle plane of a platform inside its bounds.
+#[derive(Default)]
+struct TunnellingCheckSystem {
+    last_positions: HashMap<Entity, Vector3<f32>>,
+}
+
+impl<'s> System<'s> for TunnellingCheckSystem {
+    type SystemData = (
+        Entities<'s>,
+        Write<'s, TunnellingReport>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, ColliderShape>,
+        ReadStorage<'s, Bullet>,
+    );
+
+    fn run(&mut self, (entities, mut report, transforms, shapes, bullets): Self::SystemData) {
+        for (entity, transform, _) in (&*entities, &transforms, &bullets).join() {
+            let position = translation_f32(transform);
+
+            if let Some(last_position) = self.last_positions.insert(entity, position) {
+                for (platform_transform, shape, _) in (&transforms, &shapes, !&bullets).join() {
+                    if let ShapeDesc::Cube { half_extents } = shape.desc {
+                        if crosses_box(platform_transform, &half_extents, &last_position, &position) {
+                            report.tunnelled += 1;
+                        }
+                    }
+                }
+            }
+
+            // Bullets that fell out of the scene are done.
+            if position.y < -30.0 {
+                entities.delete(entity).unwrap();
+                self.last_positions.remove(&entity);
+            }
+        }
+    }
+}
+
+/// Returns true when the segment passes through the middle plane of the box, inside its face.
+fn crosses_box(
+    transform: &Transform,
+    half_extents: &Vector3<f32>,
+    from: &Vector3<f32>,
+    to: &Vector3<f32>,
+) -> bool {
+    let inverse_rotation = rotation_f32(transform).inverse();
+    let translation = translation_f32(transform);
+    let from = inverse_rotation * (from - translation);
+    let to = inverse_rotation * (to - translation);
+
+    if from.z.signum() == to.z.signum() {
+        return false;
+    }
+
+    let t = from.z / (from.z - to.z);
+    let hit = from + (to - from) * t;
+    hit.x.abs() <= half_extents.x && hit.y.abs() <= half_extents.y
+}
@@ -0,0 +1,211 @@
+use amethyst::{
+    core::math::{Isometry3, Translation3, UnitQuaternion, Vector3},
+    phythyst::objects::ShapeDesc,
+    renderer::{
+        rendy::mesh::{Normal, Position, Tangent, TexCoord},
+        shape::Shape,
+        types,
+    },
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Serializable version of the `Shape` used to generate meshes.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum MeshShape {
+    Cube,
+    Sphere(usize, usize),
+    Cylinder(usize),
+    Cone(usize),
+    IcoSphere(usize),
+}
+
+impl MeshShape {
+    pub fn shape(&self) -> Shape {
+        match self {
+            MeshShape::Cube => Shape::Cube,
+            MeshShape::Sphere(u, v) => Shape::Sphere(*u, *v),
+            MeshShape::Cylinder(u) => Shape::Cylinder(*u, Some(1usize)),
+            MeshShape::Cone(u) => Shape::Cone(*u),
+            MeshShape::IcoSphere(subdivides) => Shape::IcoSphere(Some(*subdivides)),
+        }
+    }
+
+    /// Returns the vertices of the generated mesh, three per triangle.
+    pub fn vertices(&self, scale: [f32; 3]) -> Vec<Vector3<f32>> {
+        let (positions, _, _, _) = self
+            .shape()
+            .generate_vertices::<(Vec<Position>, Vec<Normal>, Vec<Tangent>, Vec<TexCoord>)>(Some(
+                (scale[0], scale[1], scale[2]),
+            ));
+
+        positions
+            .iter()
+            .map(|p| Vector3::new(p.0[0], p.0[1], p.0[2]))
+            .collect()
+    }
+
+    pub fn mesh_data(&self, scale: [f32; 3]) -> types::MeshData {
+        self.shape()
+            .generate::<(Vec<Position>, Vec<Normal>, Vec<Tangent>, Vec<TexCoord>)>(Some((
+                scale[0], scale[1], scale[2],
+            )))
+            .into()
+    }
+}
+
+/// Offset of a shape relative to the body that owns it.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ShapeOffset {
+    #[serde(default)]
+    pub translation: [f32; 3],
+    /// Euler angles, in degrees.
+    #[serde(default)]
+    pub rotation: [f32; 3],
+}
+
+impl ShapeOffset {
+    pub fn isometry(&self) -> Isometry3<f32> {
+        Isometry3::from_parts(
+            Translation3::new(self.translation[0], self.translation[1], self.translation[2]),
+            UnitQuaternion::from_euler_angles(
+                self.rotation[0].to_radians(),
+                self.rotation[1].to_radians(),
+                self.rotation[2].to_radians(),
+            ),
+        )
+    }
+}
+
+/// Describes a collider that can be written in the level data.
+///
+/// Cylinders and capsules are aligned along the Z axis, like `Shape::Cylinder`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ColliderDesc {
+    Sphere {
+        radius: f32,
+    },
+    Box {
+        half_extents: [f32; 3],
+    },
+    Capsule {
+        half_height: f32,
+        radius: f32,
+    },
+    Cylinder {
+        half_height: f32,
+        radius: f32,
+    },
+    /// Convex hull of the mesh generated by `shape`.
+    ConvexHull {
+        shape: MeshShape,
+        scale: [f32; 3],
+    },
+    /// Triangle mesh generated by `shape`; use it only for static bodies.
+    TriMesh {
+        shape: MeshShape,
+        scale: [f32; 3],
+    },
+    Compound(Vec<(ShapeOffset, ColliderDesc)>),
+}
+
+/// A piece of mesh used to render a collider.
+pub struct RenderPart {
+    pub offset: Isometry3<f32>,
+    pub mesh_data: types::MeshData,
+}
+
+impl ColliderDesc {
+    pub fn shape_desc(&self) -> ShapeDesc<f32> {
+        match self {
+            ColliderDesc::Sphere { radius } => ShapeDesc::Sphere { radius: *radius },
+            ColliderDesc::Box { half_extents } => ShapeDesc::Cube {
+                half_extents: Vector3::new(half_extents[0], half_extents[1], half_extents[2]),
+            },
+            ColliderDesc::Capsule {
+                half_height,
+                radius,
+            } => ShapeDesc::Capsule {
+                half_height: *half_height,
+                radius: *radius,
+            },
+            ColliderDesc::Cylinder {
+                half_height,
+                radius,
+            } => ShapeDesc::Cylinder {
+                half_height: *half_height,
+                radius: *radius,
+            },
+            ColliderDesc::ConvexHull { shape, scale } => ShapeDesc::Convex {
+                points: shape.vertices(*scale),
+            },
+            ColliderDesc::TriMesh { shape, scale } => {
+                let points = shape.vertices(*scale);
+                // The generated vertices are not indexed, each triple is a triangle.
+                let indices = (0..points.len() / 3)
+                    .map(|i| [i * 3, i * 3 + 1, i * 3 + 2])
+                    .collect();
+                ShapeDesc::TriMesh { points, indices }
+            }
+            ColliderDesc::Compound(shapes) => ShapeDesc::Compound {
+                shapes: shapes
+                    .iter()
+                    .map(|(offset, collider)| (offset.isometry(), collider.shape_desc()))
+                    .collect(),
+            },
+        }
+    }
+
+    /// Returns the meshes that draw this collider.
+    pub fn render_parts(&self) -> Vec<RenderPart> {
+        let mut parts = Vec::new();
+        self.collect_render_parts(Isometry3::identity(), &mut parts);
+        parts
+    }
+
+    fn collect_render_parts(&self, offset: Isometry3<f32>, parts: &mut Vec<RenderPart>) {
+        match self {
+            ColliderDesc::Sphere { radius } => parts.push(RenderPart {
+                offset,
+                mesh_data: MeshShape::Sphere(32, 32).mesh_data([*radius, *radius, *radius]),
+            }),
+            ColliderDesc::Box { half_extents } => parts.push(RenderPart {
+                offset,
+                mesh_data: MeshShape::Cube.mesh_data(*half_extents),
+            }),
+            ColliderDesc::Capsule {
+                half_height,
+                radius,
+            } => {
+                parts.push(RenderPart {
+                    offset,
+                    mesh_data: MeshShape::Cylinder(32).mesh_data([*radius, *radius, *half_height]),
+                });
+                for side in &[1.0, -1.0] {
+                    parts.push(RenderPart {
+                        offset: offset * Translation3::new(0.0, 0.0, side * half_height),
+                        mesh_data: MeshShape::Sphere(32, 32).mesh_data([*radius, *radius, *radius]),
+                    });
+                }
+            }
+            ColliderDesc::Cylinder {
+                half_height,
+                radius,
+            } => parts.push(RenderPart {
+                offset,
+                mesh_data: MeshShape::Cylinder(64).mesh_data([*radius, *radius, *half_height]),
+            }),
+            ColliderDesc::ConvexHull { shape, scale } | ColliderDesc::TriMesh { shape, scale } => {
+                parts.push(RenderPart {
+                    offset,
+                    mesh_data: shape.mesh_data(*scale),
+                })
+            }
+            ColliderDesc::Compound(shapes) => {
+                for (shape_offset, collider) in shapes {
+                    collider.collect_render_parts(offset * shape_offset.isometry(), parts);
+                }
+            }
+        }
+    }
+}
@@ -6,10 +6,20 @@ use amethyst::{
     },
     renderer::{
         mtl::Material,
+        palette::{LinSrgba, Srgba},
+        resources::Tint,
+        types::Mesh,
     },
     core::math::*,
+    phythyst::objects::ShapeDesc,
 };
 
+use serde::{Deserialize, Serialize};
+
+/// An impulse applied by the `ImpulseSystem` at the next dispatch.
+///
+/// Use it for deferred impulses; the velocity a body has when spawned goes
+/// in the `InitialVelocity` passed to `create_rigid_body`.
 pub struct PhysicalImpulse{
     pub impulse: Vector3<f32>,
 }
@@ -24,6 +34,63 @@ impl Component for PhysicalImpulse {
     type Storage = HashMapStorage<Self>;
 }
 
+/// Identifies an entity spawned by the level, so a `PhysicsSnapshot` can find it again.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SnapshotId(pub u32);
+
+impl Component for SnapshotId {
+    type Storage = VecStorage<Self>;
+}
+
+/// An action to perform on a rigid body, see `PhysicsCommands`.
+#[derive(Clone, Debug)]
+pub enum PhysicsCommand {
+    /// Linear impulse at the center of mass.
+    Impulse(Vector3<f32>),
+    /// Linear impulse at a world space point; it also makes the body spin.
+    ImpulseAtPoint {
+        impulse: Vector3<f32>,
+        point: Point3<f32>,
+    },
+    AngularImpulse(Vector3<f32>),
+    /// Force applied every physics step, for `duration` seconds of physics time.
+    Force {
+        force: Vector3<f32>,
+        duration: f32,
+    },
+    /// Torque applied every physics step, for `duration` seconds of physics time.
+    Torque {
+        torque: Vector3<f32>,
+        duration: f32,
+    },
+    SetLinearVelocity(Vector3<f32>),
+    SetAngularVelocity(Vector3<f32>),
+}
+
+/// Queue of commands executed by the `ImpulseSystem` on the body of this entity.
+///
+/// The one shot commands are removed once executed, while forces and torques are
+/// kept until their duration expires.
+pub struct PhysicsCommands{
+    pub commands: Vec<PhysicsCommand>,
+}
+
+impl PhysicsCommands {
+    pub fn new() -> Self {
+        PhysicsCommands{
+            commands: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, command: PhysicsCommand) {
+        self.commands.push(command);
+    }
+}
+
+impl Component for PhysicsCommands {
+    type Storage = HashMapStorage<Self>;
+}
+
 pub struct SafeZone{
     pub activation_timer: f32,
 }
@@ -41,8 +108,7 @@ impl Component for SafeZone{
 }
 
 pub struct SafeZoneAssets{
-    pub idle: Handle<Material>,
-    pub active: Handle<Material>,
+    pub material: Handle<Material>,
 }
 
 pub struct Bullet{}
@@ -56,3 +122,234 @@ impl Default for Bullet{
 impl Component for Bullet {
     type Storage = NullStorage<Self>;
 }
+
+/// A body that gives points when hit by a bullet.
+pub struct Target{}
+
+impl Default for Target{
+    fn default() -> Self {
+        Target{}
+    }
+}
+
+impl Component for Target {
+    type Storage = NullStorage<Self>;
+}
+
+/// Keeps the description used to create the physics shape of this entity.
+/// It's used to draw the collider, since the shape server doesn't expose it.
+pub struct ColliderShape{
+    pub desc: ShapeDesc<f32>,
+}
+
+impl ColliderShape {
+    pub fn new(desc: ShapeDesc<f32>) -> Self {
+        ColliderShape{desc}
+    }
+}
+
+impl Component for ColliderShape {
+    type Storage = VecStorage<Self>;
+}
+
+/// Remembers where a fast body was in the previous frame, so the swept path
+/// can be tested against areas that would be tunnelled otherwise.
+pub struct SweptMotion{
+    pub last_position: Option<Vector3<f32>>,
+}
+
+impl Default for SweptMotion{
+    fn default() -> Self {
+        SweptMotion{
+            last_position: None,
+        }
+    }
+}
+
+impl Component for SweptMotion {
+    type Storage = VecStorage<Self>;
+}
+
+/// A point that pulls, or pushes when `strength` is negative, the bodies in the area.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Attractor {
+    pub point: [f32; 3],
+    pub strength: f32,
+}
+
+/// Changes the physics of the bodies inside the area of this entity.
+/// Accelerations are in m/s².
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhysicsModifier {
+    /// Replaces the world gravity.
+    pub gravity: Option<[f32; 3]>,
+    pub wind: [f32; 3],
+    /// Slows down the bodies proportionally to their velocity.
+    pub drag: f32,
+    pub attractor: Option<Attractor>,
+}
+
+impl Component for PhysicsModifier {
+    type Storage = HashMapStorage<Self>;
+}
+
+/// How a `ParticleEmitter` spawns its particles.
+#[derive(Clone, Debug)]
+pub enum EmitterMode {
+    /// Spawns `count` particles at once, then the emitter entity is deleted.
+    Burst { count: usize },
+    /// Spawns `rate` particles per second, until the emitter is removed.
+    Continuous { rate: f32 },
+}
+
+/// Spawns particles at the position of this entity, see `ParticleSystem`.
+#[derive(Clone, Debug)]
+pub struct ParticleEmitter {
+    pub mode: EmitterMode,
+    /// Seconds each particle lives.
+    pub lifetime: f32,
+    /// Main direction of the particles.
+    pub direction: Vector3<f32>,
+    /// `0.0` emits along `direction`, `1.0` in all the directions.
+    pub spread: f32,
+    pub min_speed: f32,
+    pub max_speed: f32,
+    /// Acceleration applied to the particles, in m/s².
+    pub gravity: Vector3<f32>,
+    pub start_size: f32,
+    pub end_size: f32,
+    /// Color at spawn, faded towards `end_color` over the lifetime.
+    pub start_color: [f32; 4],
+    pub end_color: [f32; 4],
+    /// Particles owed by the continuous mode.
+    pub spawn_accumulator: f32,
+}
+
+impl ParticleEmitter {
+    /// Sparks of a bullet hitting a surface with the given normal.
+    pub fn impact(normal: Vector3<f32>) -> Self {
+        ParticleEmitter {
+            mode: EmitterMode::Burst { count: 12 },
+            lifetime: 0.4,
+            direction: normal,
+            spread: 0.6,
+            min_speed: 4.0,
+            max_speed: 10.0,
+            gravity: Vector3::new(0.0, -9.8, 0.0),
+            start_size: 0.15,
+            end_size: 0.05,
+            start_color: [1.0, 0.8, 0.3, 1.0],
+            end_color: [1.0, 0.2, 0.0, 0.0],
+            spawn_accumulator: 0.0,
+        }
+    }
+
+    /// A bullet dissolving into a safe zone.
+    pub fn absorption() -> Self {
+        ParticleEmitter {
+            mode: EmitterMode::Burst { count: 30 },
+            lifetime: 0.8,
+            direction: Vector3::y(),
+            spread: 1.0,
+            min_speed: 1.0,
+            max_speed: 4.0,
+            gravity: Vector3::zeros(),
+            start_size: 0.3,
+            end_size: 0.0,
+            start_color: [1.0, 0.3, 0.3, 0.8],
+            end_color: [1.0, 1.0, 1.0, 0.0],
+            spawn_accumulator: 0.0,
+        }
+    }
+}
+
+impl Component for ParticleEmitter {
+    type Storage = HashMapStorage<Self>;
+}
+
+/// A particle spawned by a `ParticleEmitter`.
+pub struct Particle {
+    pub age: f32,
+    pub lifetime: f32,
+    pub velocity: Vector3<f32>,
+    pub gravity: Vector3<f32>,
+    pub start_size: f32,
+    pub end_size: f32,
+    pub start_color: [f32; 4],
+    pub end_color: [f32; 4],
+}
+
+impl Component for Particle {
+    type Storage = VecStorage<Self>;
+}
+
+/// Mesh and material shared by all the particles; the color is set with a `Tint`.
+pub struct ParticleAssets {
+    pub mesh: Handle<Mesh>,
+    pub material: Handle<Material>,
+}
+
+/// Fades the `Tint` of this entity between the idle and active colors.
+///
+/// The material handle stays the same, see `MaterialAnimationSystem`.
+pub struct MaterialAnimation {
+    pub idle_color: LinSrgba,
+    pub active_color: LinSrgba,
+    /// Set by the gameplay systems.
+    pub active: bool,
+    /// Seconds to go from idle to active.
+    pub fade_in: f32,
+    /// Seconds to go from active back to idle.
+    pub fade_out: f32,
+    /// Pulses per second while active.
+    pub pulse_frequency: f32,
+    /// Fraction of the active color removed at the bottom of a pulse.
+    pub pulse_amount: f32,
+    /// `0.0` when idle, `1.0` when active.
+    pub blend: f32,
+    pub pulse_time: f32,
+}
+
+impl MaterialAnimation {
+    pub fn new(idle_color: LinSrgba, active_color: LinSrgba) -> Self {
+        MaterialAnimation {
+            idle_color,
+            active_color,
+            active: false,
+            fade_in: 0.1,
+            fade_out: 0.6,
+            pulse_frequency: 2.0,
+            pulse_amount: 0.3,
+            blend: 0.0,
+            pulse_time: 0.0,
+        }
+    }
+
+    /// The tint for the current state of the animation.
+    pub fn tint(&self) -> Tint {
+        // Smoothstep, so the transitions ease in and out.
+        let t = self.blend * self.blend * (3.0 - 2.0 * self.blend);
+        let pulse = 1.0
+            - self.pulse_amount
+                * (0.5 - 0.5 * (self.pulse_time * self.pulse_frequency * 2.0 * std::f32::consts::PI).cos());
+        let active = LinSrgba::new(
+            self.active_color.red * pulse,
+            self.active_color.green * pulse,
+            self.active_color.blue * pulse,
+            self.active_color.alpha * pulse,
+        );
+
+        let idle = &self.idle_color;
+        Tint(Srgba::from_linear(LinSrgba::new(
+            idle.red + (active.red - idle.red) * t,
+            idle.green + (active.green - idle.green) * t,
+            idle.blue + (active.blue - idle.blue) * t,
+            idle.alpha + (active.alpha - idle.alpha) * t,
+        )))
+    }
+}
+
+impl Component for MaterialAnimation {
+    type Storage = HashMapStorage<Self>;
+}
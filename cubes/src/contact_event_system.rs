@@ -0,0 +1,107 @@
+use crate::physics_tag_index::PhysicsTagIndex;
+
+use amethyst::{
+    core::{
+        math::{Point3, Vector3},
+        shrev::EventChannel,
+    },
+    ecs::{Entities, Entity, Join, Read, ReadExpect, ReadStorage, System, Write},
+    phythyst::{objects::*, servers::*},
+};
+
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ContactEventKind {
+    Began,
+    Ended,
+}
+
+/// A contact of the body of `entity` with the body of `other`.
+///
+/// Each body emits its own events, so a contact between two bodies produces two events;
+/// readers just have to filter by `entity`.
+#[derive(Clone, Debug)]
+pub struct ContactEvent {
+    pub kind: ContactEventKind,
+    pub entity: Entity,
+    /// `None` when the other entity is already deleted.
+    pub other: Option<Entity>,
+    /// Magnitude of the impulse that resolved the contact, `0.0` for the `Ended` events.
+    pub impulse: f32,
+    pub point: Point3<f32>,
+    pub normal: Vector3<f32>,
+}
+
+/// The last known state of a contact, used to emit the `Ended` event.
+struct ActiveContact {
+    entity: Entity,
+    point: Point3<f32>,
+    normal: Vector3<f32>,
+}
+
+/// Compares the contacts of this step with the ones of the previous step and
+/// writes the differences into the `EventChannel<ContactEvent>`.
+///
+/// #IMPORTANT
+/// Like the `SafeZoneSystem`, it must be executed after the physics stepping.
+#[derive(Default)]
+pub struct ContactEventSystem {
+    active_contacts: HashMap<(PhysicsBodyTag, PhysicsBodyTag), ActiveContact>,
+}
+
+impl<'s> System<'s> for ContactEventSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadExpect<'s, RBodyPhysicsServer<f32>>,
+        Read<'s, PhysicsTagIndex>,
+        Write<'s, EventChannel<ContactEvent>>,
+        ReadStorage<'s, PhysicsBodyTag>,
+    );
+
+    fn run(&mut self, (entities, body_server, tag_index, mut channel, bodies): Self::SystemData) {
+        let mut current_contacts = HashMap::with_capacity(self.active_contacts.len());
+
+        for (entity, body) in (&*entities, &bodies).join() {
+            for contact in body_server.contact_events(*body) {
+                let key = (*body, contact.other_body);
+                let point = Point3::from(contact.contact_point);
+
+                if !self.active_contacts.contains_key(&key) {
+                    channel.single_write(ContactEvent {
+                        kind: ContactEventKind::Began,
+                        entity,
+                        other: tag_index.body_entity(contact.other_body),
+                        impulse: contact.impulse,
+                        point,
+                        normal: contact.normal,
+                    });
+                }
+
+                current_contacts.insert(
+                    key,
+                    ActiveContact {
+                        entity,
+                        point,
+                        normal: contact.normal,
+                    },
+                );
+            }
+        }
+
+        for (key, contact) in self.active_contacts.drain() {
+            if !current_contacts.contains_key(&key) {
+                channel.single_write(ContactEvent {
+                    kind: ContactEventKind::Ended,
+                    entity: contact.entity,
+                    other: tag_index.body_entity(key.1),
+                    impulse: 0.0,
+                    point: contact.point,
+                    normal: contact.normal,
+                });
+            }
+        }
+
+        self.active_contacts = current_contacts;
+    }
+}
@@ -0,0 +1,179 @@
+use amethyst::{
+    config::Config,
+    core::Time,
+    ecs::{ReadExpect, System, WriteExpect},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Rules of a round, loaded from `configs/game_mode_conf.ron`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameModeConfig {
+    /// Length of a round, in seconds.
+    pub round_length: f32,
+    /// Seconds after a hit during which the next one raises the combo.
+    pub combo_window: f32,
+    /// The combo multiplier can't go over this value.
+    pub max_combo: u32,
+    /// Bullets that can be fired during a round.
+    pub ammo: u32,
+    pub safe_zone_points: u32,
+    pub target_points: u32,
+}
+
+impl Default for GameModeConfig {
+    fn default() -> Self {
+        GameModeConfig {
+            round_length: 60.0,
+            combo_window: 2.0,
+            max_combo: 5,
+            ammo: 30,
+            safe_zone_points: 100,
+            target_points: 250,
+        }
+    }
+}
+
+/// The progress of the current round.
+pub struct GameMode {
+    pub config: GameModeConfig,
+    pub round_time_left: f32,
+    pub score: u32,
+    pub combo: u32,
+    pub combo_time_left: f32,
+    pub bullets_fired: u32,
+}
+
+impl GameMode {
+    pub fn new(config: GameModeConfig) -> Self {
+        GameMode {
+            round_time_left: config.round_length,
+            config,
+            score: 0,
+            combo: 0,
+            combo_time_left: 0.0,
+            bullets_fired: 0,
+        }
+    }
+
+    pub fn bullets_left(&self) -> u32 {
+        self.config.ammo.saturating_sub(self.bullets_fired)
+    }
+
+    pub fn is_round_over(&self) -> bool {
+        self.round_time_left <= 0.0
+    }
+
+    /// Adds the points of a hit, multiplied by the current combo.
+    pub fn register_hit(&mut self, points: u32) {
+        if self.is_round_over() {
+            return;
+        }
+
+        if self.combo_time_left > 0.0 {
+            self.combo = (self.combo + 1).min(self.config.max_combo);
+        } else {
+            self.combo = 1;
+        }
+        self.combo_time_left = self.config.combo_window;
+
+        self.score += points * self.combo;
+    }
+}
+
+/// Advances the round timer and expires the combo.
+#[derive(Default)]
+pub struct GameModeSystem;
+
+impl<'s> System<'s> for GameModeSystem {
+    type SystemData = (ReadExpect<'s, Time>, Option<WriteExpect<'s, GameMode>>);
+
+    fn run(&mut self, (time, game_mode): Self::SystemData) {
+        let mut game_mode = match game_mode {
+            Some(game_mode) => game_mode,
+            None => return,
+        };
+
+        let delta = time.delta_seconds();
+        game_mode.round_time_left = (game_mode.round_time_left - delta).max(0.0);
+
+        game_mode.combo_time_left -= delta;
+        if game_mode.combo_time_left <= 0.0 {
+            game_mode.combo_time_left = 0.0;
+            game_mode.combo = 0;
+        }
+    }
+}
+
+/// Number of scores kept in the high scores file.
+const MAX_HIGH_SCORES: usize = 10;
+
+/// The best scores, saved to `high_scores.ron`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    pub scores: Vec<u32>,
+}
+
+impl HighScores {
+    pub fn path() -> String {
+        crate::get_dir_path("/high_scores.ron")
+    }
+
+    /// Adds the score and returns its rank, starting from 0, if it made the list.
+    ///
+    /// A round without points never makes the list.
+    pub fn submit(&mut self, score: u32) -> Option<usize> {
+        if score == 0 {
+            return None;
+        }
+
+        let rank = self.scores.iter().position(|s| score > *s).unwrap_or(self.scores.len());
+        if rank >= MAX_HIGH_SCORES {
+            return None;
+        }
+
+        self.scores.insert(rank, score);
+        self.scores.truncate(MAX_HIGH_SCORES);
+        Some(rank)
+    }
+
+    pub fn save(&self) {
+        if let Err(e) = self.write(Self::path()) {
+            log::error!("Can't save the high scores: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_are_kept_sorted() {
+        let mut high_scores = HighScores::default();
+        assert_eq!(high_scores.submit(100), Some(0));
+        assert_eq!(high_scores.submit(300), Some(0));
+        assert_eq!(high_scores.submit(200), Some(1));
+        assert_eq!(high_scores.scores, vec![300, 200, 100]);
+    }
+
+    #[test]
+    fn only_the_best_scores_are_kept() {
+        let mut high_scores = HighScores::default();
+        for score in 1..=MAX_HIGH_SCORES as u32 {
+            high_scores.submit(score * 10);
+        }
+        assert_eq!(high_scores.submit(5), None);
+        assert_eq!(high_scores.submit(15), Some(MAX_HIGH_SCORES - 1));
+        assert_eq!(high_scores.scores.len(), MAX_HIGH_SCORES);
+        assert_eq!(high_scores.scores.last(), Some(&15));
+    }
+
+    #[test]
+    fn zero_is_not_a_high_score() {
+        let mut high_scores = HighScores::default();
+        assert_eq!(high_scores.submit(0), None);
+        assert!(high_scores.scores.is_empty());
+    }
+}
@@ -1,5 +1,21 @@
 use crate::{
+    audio::SoundEvent,
+    collider::ColliderDesc,
     components::*,
+    game_mode::{GameMode, GameModeConfig},
+    hud_system::hud_prefab_path,
+    level::{LevelDesc, ObstacleDesc, ZoneDesc},
+    lighting,
+    material_library::{material_or_color, MaterialLibrary},
+    physics_cleanup,
+    physics_material::PhysicsMaterial,
+    physics_stats,
+    physics_tag_index::PhysicsTagIndex,
+    snapshot::{restore_body, PhysicsSnapshot},
+    pause_state::PauseState,
+    platform::PlatformDesc,
+    results_state::ResultsState,
+    transform_utils::transform_from_isometry,
     safe_zone_system::SafeZoneSystem,
 };
 
@@ -7,61 +23,111 @@ use amethyst::{
     assets::{AssetStorage, Handle, Loader},
     core::{
         math::{Vector, Vector3},
-        Time, Transform, Float,
+        shrev::EventChannel,
+        Time, Transform, Float, Parent,
     },
-    ecs::prelude::World,
-    input::{InputEvent, InputHandler, StringBindings},
+    config::Config,
+    ecs::prelude::{Entity, Join, World},
+    input::{is_key_down, InputEvent, InputHandler, StringBindings, VirtualKeyCode},
     phythyst::{objects::*, servers::*},
     prelude::{Builder, GameData, SimpleState, SimpleTrans, StateData, Trans},
     renderer::{
-        camera, light, mtl,
-        palette::{LinSrgba, Srgb},
-        rendy::{
-            mesh::{Normal, Position, Tangent, TexCoord},
-            texture,
-        },
+        camera,
+        palette::{LinSrgba, Srgba},
+        resources::Tint,
+        rendy::mesh::{Normal, Position, Tangent, TexCoord},
         Transparent,
         shape::Shape,
         types,
     },
+    ui::UiCreator,
     window::ScreenDimensions,
     StateEvent,
 };
 
 use rand::prelude::*;
 
+use std::path::PathBuf;
+
 const SAFE_ZONE_RADIUS :f32 = 10.0;
 
+/// Speed of the bullets when fired, in m/s.
+const BULLET_SPEED: f32 = 100.0;
+
+/// A physics shape together with the description used to create it.
+struct PhysicsShape {
+    tag: PhysicsShapeTag,
+    desc: ShapeDesc<f32>,
+}
+
 pub struct CubeGameState {
+    level: PathBuf,
     bullet_fired: bool,
-    bullet_shape: Option<PhysicsShapeTag>,
-    platform_shape: Option<PhysicsShapeTag>,
-    safe_zone_area: Option<PhysicsShapeTag>,
+    bullet_shape: Option<PhysicsShape>,
+    platform: PlatformDesc,
+    platform_shape: Option<PhysicsShape>,
+    safe_zone_area: Option<PhysicsShape>,
+    /// Shapes of the obstacles and zones of the level.
+    level_shapes: Vec<PhysicsShapeTag>,
     camera_transform: Transform,
+    next_snapshot_id: u32,
+    /// Snapshot restored once the level is loaded.
+    initial_snapshot: Option<PhysicsSnapshot>,
+    snapshot_key_was_down: bool,
 }
 
 impl CubeGameState {
     pub fn new() -> Self {
         CubeGameState {
+            level: PathBuf::from(crate::get_dir_path("/levels/arena.ron")),
             bullet_fired: false,
             bullet_shape: None,
+            platform: PlatformDesc::Box {
+                half_extents: Vector3::new(10.0, 10.0, 0.3),
+            },
             platform_shape: None,
             safe_zone_area: None,
+            level_shapes: Vec::new(),
             camera_transform: Transform::default(),
+            next_snapshot_id: 0,
+            initial_snapshot: None,
+            snapshot_key_was_down: false,
         }
     }
+
+    /// Plays the level stored in this file, instead of the arena.
+    pub fn with_level(mut self, level: PathBuf) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Starts the game from the given snapshot.
+    pub fn with_snapshot(mut self, snapshot: PhysicsSnapshot) -> Self {
+        self.initial_snapshot = Some(snapshot);
+        self
+    }
 }
 
 impl SimpleState for CubeGameState {
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
 
         data.world.register::<Bullet>();
+        data.world.register::<SnapshotId>();
+        data.world.register::<Target>();
+
+        data.world
+            .add_resource(MaterialLibrary::load(MaterialLibrary::path()));
+
+        data.world.add_resource(GameMode::new(GameModeConfig::load(crate::get_dir_path(
+            "/configs/game_mode_conf.ron",
+        ))));
 
         let mut transf = Transform::default();
 
         self.initialize_bullet_shape(data.world, 0.5);
         self.initialize_platform_shape(data.world);
         self.initialize_safe_zone(data.world);
+        self.initialize_particles(data.world);
 
         transf.append_rotation_x_axis(90.0f32.to_radians());
         self.add_cube(data.world, &transf);
@@ -78,58 +144,172 @@ impl SimpleState for CubeGameState {
         transf.set_rotation_x_axis(0.0f32.to_radians());
         self.add_cube(data.world, &transf);
 
-        self.add_light_entity(data.world, Vector3::new(-1.0, -1.0, -1.0));
+        let level = LevelDesc::load(&self.level);
+
+        lighting::add_lights(data.world, &level.lighting);
 
         self.add_camera_entity(data.world);
 
+        physics_stats::create_overlay(data.world);
+
+        data.world.exec(|mut creator: UiCreator<'_>| {
+            creator.create(hud_prefab_path(), ());
+        });
+
         self.add_safe_zone(data.world, &Transform::default());
+
+        for obstacle in level.obstacles.iter() {
+            self.add_obstacle(data.world, obstacle);
+        }
+        for zone in level.zones.iter() {
+            self.add_zone(data.world, zone);
+        }
+
+        if let Some(snapshot) = self.initial_snapshot.take() {
+            self.restore_snapshot(data.world, &snapshot);
+        }
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        physics_cleanup::destroy_bodies_and_areas(data.world);
+
+        // The next game creates the shapes again.
+        let mut shapes: Vec<PhysicsShapeTag> = self.level_shapes.drain(..).collect();
+        shapes.extend(
+            [
+                self.bullet_shape.take(),
+                self.platform_shape.take(),
+                self.safe_zone_area.take(),
+            ]
+            .iter()
+            .flatten()
+            .map(|shape| shape.tag),
+        );
+        physics_cleanup::destroy_shapes(data.world, &shapes);
+
+        data.world.delete_all();
+
+        let remaining = physics_cleanup::remaining_bodies(data.world);
+        if remaining > 0 {
+            log::error!("{} physics bodies survived the end of the game.", remaining);
+        }
+    }
+
+    fn handle_event(
+        &mut self,
+        _data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        match event {
+            StateEvent::Window(event) if is_key_down(&event, VirtualKeyCode::Escape) => {
+                Trans::Push(Box::new(PauseState::new()))
+            }
+            _ => Trans::None,
+        }
     }
 
     fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
         let mut want_to_fire = false;
+        let mut want_to_save = false;
+        let mut want_to_load = false;
         {
             let ih = data.world.read_resource::<InputHandler<StringBindings>>();
 
             want_to_fire = ih.action_is_down("shot").unwrap();
+            want_to_save = ih.action_is_down("save_snapshot").unwrap_or(false);
+            want_to_load = ih.action_is_down("load_snapshot").unwrap_or(false);
+        }
+
+        if (want_to_save || want_to_load) && !self.snapshot_key_was_down {
+            let path = crate::get_dir_path("/snapshots/snapshot.ron");
+            if want_to_save {
+                match PhysicsSnapshot::capture(data.world).save(&path) {
+                    Ok(()) => log::info!("Physics snapshot saved to {}", path),
+                    Err(e) => log::error!("Can't save the physics snapshot: {}", e),
+                }
+            } else {
+                match PhysicsSnapshot::load(&path) {
+                    Ok(snapshot) => self.restore_snapshot(data.world, &snapshot),
+                    Err(e) => log::error!("Can't load the physics snapshot: {}", e),
+                }
+            }
         }
+        self.snapshot_key_was_down = want_to_save || want_to_load;
 
+        let has_bullets = data.world.read_resource::<GameMode>().bullets_left() > 0;
         if want_to_fire {
-            if !self.bullet_fired {
+            if !self.bullet_fired && has_bullets {
                 self.bullet_fired = true;
 
-                let impulse = self.camera_transform.rotation() * Vector3::z();
-                let impulse = Vector3::new(impulse.x.into(), impulse.y.into(), impulse.z.into());
-                let impulse = impulse * -1.0 * 100.0;
+                let velocity = self.camera_transform.rotation() * Vector3::z();
+                let velocity = Vector3::new(velocity.x.into(), velocity.y.into(), velocity.z.into());
+                let velocity = velocity * -1.0 * BULLET_SPEED;
 
                 self.add_bullet_entity(
                     data.world,
                     &self.camera_transform,
                     0.5,
-                    &impulse,
+                    &velocity,
                 );
+                data.world.write_resource::<GameMode>().bullets_fired += 1;
+                data.world
+                    .write_resource::<EventChannel<SoundEvent>>()
+                    .single_write(SoundEvent::Shot);
             }
         } else {
             self.bullet_fired = false;
         }
 
-        Trans::None
+        let game_mode = data.world.read_resource::<GameMode>();
+        if game_mode.is_round_over() {
+            Trans::Switch(Box::new(ResultsState::new(game_mode.score, self.level.clone())))
+        } else {
+            Trans::None
+        }
     }
 }
 
 impl CubeGameState {
 
+    fn next_snapshot_id(&mut self) -> SnapshotId {
+        self.next_snapshot_id += 1;
+        SnapshotId(self.next_snapshot_id)
+    }
+
+    /// Puts the arena back to the captured state; the current bullets are replaced
+    /// by the ones in the snapshot.
+    fn restore_snapshot(&self, world: &mut World, snapshot: &PhysicsSnapshot) {
+        let bullets: Vec<Entity> = (&world.entities(), &world.read_storage::<Bullet>())
+            .join()
+            .map(|(e, _)| e)
+            .collect();
+        physics_cleanup::destroy_physics_of(world, &bullets);
+        world.delete_entities(&bullets).unwrap();
+
+        snapshot.restore_level(world);
+
+        for state in snapshot.bullets.iter() {
+            let bullet = self.add_bullet_entity(world, &state.transform(), 0.5, &state.linear_velocity());
+            restore_body(world, bullet, state);
+        }
+    }
+
     fn initialize_bullet_shape(&mut self, world: &mut World, radius: f32) {
         let mut shape_server = world.write_resource::<ShapePhysicsServer<f32>>();
         let shape_desc = ShapeDesc::Sphere { radius };
-        self.bullet_shape = Some(shape_server.create_shape(&shape_desc));
+        self.bullet_shape = Some(PhysicsShape {
+            tag: shape_server.create_shape(&shape_desc),
+            desc: shape_desc,
+        });
     }
 
     fn initialize_platform_shape(&mut self, world: &mut World) {
         let mut shape_server = world.write_resource::<ShapePhysicsServer<f32>>();
-        let shape_desc = ShapeDesc::<f32>::Cube {
-            half_extents: Vector3::new(10.0, 10.0, 0.3),
-        };
-        self.platform_shape = Some(shape_server.create_shape(&shape_desc));
+        let shape_desc = self.platform.shape_desc();
+        self.platform_shape = Some(PhysicsShape {
+            tag: shape_server.create_shape(&shape_desc),
+            desc: shape_desc,
+        });
     }
 
     fn initialize_safe_zone(&mut self, world: &mut World) {
@@ -138,27 +318,47 @@ impl CubeGameState {
             let shape_desc = ShapeDesc::<f32>::Sphere {
                 radius: SAFE_ZONE_RADIUS,
             };
-            self.safe_zone_area = Some(shape_server.create_shape(&shape_desc));
+            self.safe_zone_area = Some(PhysicsShape {
+                tag: shape_server.create_shape(&shape_desc),
+                desc: shape_desc,
+            });
         }
 
+        // White, the colors are given by the `MaterialAnimation` of each safe zone.
         let safe_zone_assets = SafeZoneAssets{
-            idle: create_material(
+            material: material_or_color(
                 world,
-                LinSrgba::new(0.2, 0.1, 0.1, 0.05),
+                Some("safe_zone"),
+                LinSrgba::new(1.0, 1.0, 1.0, 1.0),
                 0.0,
                 1.0,
             ),
-            active: create_material(
-                world,
-                LinSrgba::new(1.0, 0.0, 0.0, 0.6),
-                0.0,
-                1.0,
-            )
         };
 
         world.add_resource(safe_zone_assets);
     }
 
+    fn initialize_particles(&self, world: &mut World) {
+        let mesh = {
+            let sphere_mesh_data: types::MeshData = Shape::Sphere(8, 8)
+                .generate::<(Vec<Position>, Vec<Normal>, Vec<Tangent>, Vec<TexCoord>)>(None)
+                .into();
+
+            create_mesh(world, sphere_mesh_data)
+        };
+
+        // White, so the `Tint` of each particle gives the color.
+        let material = material_or_color(
+            world,
+            Some("particle"),
+            LinSrgba::new(1.0, 1.0, 1.0, 1.0),
+            0.0,
+            1.0,
+        );
+
+        world.add_resource(ParticleAssets { mesh, material });
+    }
+
     fn add_camera_entity(&mut self, world: &mut World) {
         self.camera_transform.set_translation_xyz(35.0, 20.0, 35.0);
         self.camera_transform
@@ -181,8 +381,8 @@ impl CubeGameState {
         world: &mut World,
         transform: &Transform,
         radius: f32,
-        impulse: &Vector3<f32>,
-    ) {
+        velocity: &Vector3<f32>,
+    ) -> Entity {
         // Mesh
 
         let mesh = {
@@ -195,36 +395,52 @@ impl CubeGameState {
             create_mesh(world, sphere_mesh_data)
         };
 
-        let mut rng = thread_rng();
-
-        let mat = create_material(
+        // Shared by all the bullets, each one gets a random `Tint`.
+        let mat = material_or_color(
             world,
-            LinSrgba::new(rng.gen(), rng.gen(), rng.gen(), 0.2),
+            Some("bullet"),
+            LinSrgba::new(1.0, 1.0, 1.0, 0.2),
             0.3,
             0.7,
         );
 
+        let mut rng = thread_rng();
+        let tint = Tint(Srgba::new(rng.gen(), rng.gen(), rng.gen(), 1.0));
+
         // Rigid body
+        let bullet_shape = self.bullet_shape.as_ref().unwrap();
         let rb = create_rigid_body(
             world,
             &transform,
-            self.bullet_shape.unwrap(),
+            bullet_shape.tag,
             BodyMode::Dynamic,
-            impulse,
+            &PhysicsMaterial {
+                ccd: true,
+                ..PhysicsMaterial::bouncy()
+            },
+            &InitialVelocity {
+                linear: *velocity,
+                ..Default::default()
+            },
         );
 
-        world
+        let entity = world
             .create_entity()
             .with(transform.clone())
             .with(mesh)
             .with(mat)
+            .with(tint)
             .with(rb)
+            .with(ColliderShape::new(bullet_shape.desc.clone()))
             .with(Bullet::default())
-            .with(PhysicalImpulse::new(impulse.clone()))
+            .with(SweptMotion::default())
             .build();
+        world.write_resource::<PhysicsTagIndex>().insert_body(rb, entity);
+        entity
     }
 
-    fn add_safe_zone(&self, world: &mut World, transf: &Transform){
+    fn add_safe_zone(&mut self, world: &mut World, transf: &Transform){
+        let snapshot_id = self.next_snapshot_id();
 
         let mesh = {
             let sphere_mesh_data: types::MeshData = Shape::Sphere(32, 32)
@@ -236,71 +452,146 @@ impl CubeGameState {
             create_mesh(world, sphere_mesh_data)
         };
 
-        let safe_zone_mat_idle = world.read_resource::<SafeZoneAssets>().idle.clone();
+        let safe_zone_mat = world.read_resource::<SafeZoneAssets>().material.clone();
+        let animation = MaterialAnimation::new(
+            LinSrgba::new(0.2, 0.1, 0.1, 0.05),
+            LinSrgba::new(1.0, 0.0, 0.0, 0.6),
+        );
 
-        let area = create_area(world, transf, self.safe_zone_area.unwrap());
+        let safe_zone_area = self.safe_zone_area.as_ref().unwrap();
+        let area = create_area(world, transf, safe_zone_area.tag);
 
-        world
+        let entity = world
             .create_entity()
             .with(mesh)
-            .with(safe_zone_mat_idle)
+            .with(safe_zone_mat)
+            .with(animation.tint())
+            .with(animation)
             .with(Transparent::default())
             .with(transf.clone())
             .with(area)
+            .with(ColliderShape::new(safe_zone_area.desc.clone()))
             .with(SafeZone::default())
+            .with(snapshot_id)
             .build();
+        world.write_resource::<PhysicsTagIndex>().insert_area(area, entity);
     }
 
-    fn add_cube(&self, world: &mut World, transf: &Transform) {
-        let mesh = {
-            let plane_mesh_data: types::MeshData = Shape::Cylinder(128usize, Some(1usize))
-                .generate::<(Vec<Position>, Vec<Normal>, Vec<Tangent>, Vec<TexCoord>)>(Some((
-                    10.0, 10.0, 0.3, // Scale
-                )))
-                .into();
-
-            create_mesh(world, plane_mesh_data)
-        };
+    fn add_cube(&mut self, world: &mut World, transf: &Transform) {
+        let snapshot_id = self.next_snapshot_id();
+        let mesh = create_mesh(world, self.platform.mesh_data());
 
-        let mat = create_material(world, LinSrgba::new(0.0, 1.0, 0.0, 1.0), 0.5, 0.5);
+        let mat = material_or_color(
+            world,
+            Some("platform"),
+            LinSrgba::new(0.0, 1.0, 0.0, 1.0),
+            0.5,
+            0.5,
+        );
 
+        let platform_shape = self.platform_shape.as_ref().unwrap();
         let rb = create_rigid_body(
             world,
             transf,
-            self.platform_shape.unwrap(),
+            platform_shape.tag,
             BodyMode::Static,
-            &Vector3::zeros(),
+            &PhysicsMaterial::slippery(),
+            &InitialVelocity::default(),
         );
 
-        world
+        let entity = world
             .create_entity()
             .with(transf.clone())
             .with(mesh)
             .with(mat)
             .with(rb)
+            .with(ColliderShape::new(platform_shape.desc.clone()))
+            .with(snapshot_id)
             .build();
+        world.write_resource::<PhysicsTagIndex>().insert_body(rb, entity);
     }
 
-    fn add_light_entity(&self, world: &mut World, direction: Vector3<f32>) {
-        let mut t = Transform::default();
-        t.set_translation(Vector3::new(6.0, 6.0, 6.0));
+    fn add_obstacle(&mut self, world: &mut World, obstacle: &ObstacleDesc) {
+        let snapshot_id = self.next_snapshot_id();
+        let body_mode = match (&obstacle.collider, obstacle.dynamic) {
+            (ColliderDesc::TriMesh { .. }, true) => {
+                log::warn!("A triangle mesh obstacle can't be dynamic, it will be static.");
+                BodyMode::Static
+            }
+            (_, true) => BodyMode::Dynamic,
+            (_, false) => BodyMode::Static,
+        };
+
+        let shape_desc = obstacle.collider.shape_desc();
+        let shape = {
+            let mut shape_server = world.write_resource::<ShapePhysicsServer<f32>>();
+            shape_server.create_shape(&shape_desc)
+        };
+        self.level_shapes.push(shape);
 
-        let light: light::Light = light::DirectionalLight {
-            color: Srgb::new(1.0, 1.0, 1.0),
-            direction: direction.normalize(),
-            intensity: 5.0,
+        let transf = transform_from_isometry(&obstacle.transform.isometry());
+        let color = obstacle.color;
+        let mat = material_or_color(
+            world,
+            obstacle.render_material.as_ref().map(String::as_str),
+            LinSrgba::new(color[0], color[1], color[2], color[3]),
+            0.5,
+            0.5,
+        );
+
+        let rb = create_rigid_body(
+            world,
+            &transf,
+            shape,
+            body_mode,
+            &obstacle.material,
+            &InitialVelocity::default(),
+        );
+
+        let mut builder = world
+            .create_entity()
+            .with(transf)
+            .with(rb)
+            .with(ColliderShape::new(shape_desc))
+            .with(snapshot_id);
+        if obstacle.target {
+            builder = builder.with(Target::default());
         }
-        .into();
+        let body_entity = builder.build();
+        world.write_resource::<PhysicsTagIndex>().insert_body(rb, body_entity);
+
+        // Each part is rendered by a child, so compound colliders get all their meshes.
+        for part in obstacle.collider.render_parts() {
+            let mesh = create_mesh(world, part.mesh_data);
+            world
+                .create_entity()
+                .with(transform_from_isometry(&part.offset))
+                .with(Parent::new(body_entity))
+                .with(mesh)
+                .with(mat.clone())
+                .build();
+        }
+    }
+
+    fn add_zone(&mut self, world: &mut World, zone: &ZoneDesc) {
+        let shape_desc = zone.shape.shape_desc();
+        let shape = {
+            let mut shape_server = world.write_resource::<ShapePhysicsServer<f32>>();
+            shape_server.create_shape(&shape_desc)
+        };
+        self.level_shapes.push(shape);
 
-        //let light: light::Light = light::PointLight {
-        //    color: Srgb::new(1.0, 1.0, 1.0),
-        //    radius: 20.0,
-        //    intensity: 10.5,
-        //    smoothness: 20.0,
-        //}
-        //.into();
+        let transf = transform_from_isometry(&zone.transform.isometry());
+        let area = create_area(world, &transf, shape);
 
-        world.create_entity().with(light).with(t).build();
+        let entity = world
+            .create_entity()
+            .with(transf)
+            .with(area)
+            .with(ColliderShape::new(shape_desc))
+            .with(zone.modifier.clone())
+            .build();
+        world.write_resource::<PhysicsTagIndex>().insert_area(area, entity);
     }
 }
 
@@ -314,51 +605,28 @@ fn create_mesh(world: &World, mesh_data: types::MeshData) -> Handle<types::Mesh>
     mesh
 }
 
-fn create_material(
-    world: &World,
-    color: LinSrgba,
-    metallic: f32,
-    roughness: f32,
-) -> Handle<mtl::Material> {
-    let loader = world.read_resource::<Loader>();
+/// Velocity that a dynamic body has since its creation.
+pub struct InitialVelocity {
+    pub linear: Vector3<f32>,
+    pub angular: Vector3<f32>,
+}
 
-    // Material creation
-    let asset_storage = world.read_resource::<AssetStorage<types::Texture>>();
-    let albedo = loader.load_from_data(
-        texture::palette::load_from_linear_rgba(color).into(),
-        (),
-        &asset_storage,
-    );
-
-    let metallic_roughness = loader.load_from_data(
-        texture::palette::load_from_linear_rgba(LinSrgba::new(0.0, roughness, metallic, 0.0))
-            .into(),
-        (),
-        &asset_storage,
-    );
-
-    let asset_storage = world.read_resource::<AssetStorage<mtl::Material>>();
-    let mat_defaults = world.read_resource::<mtl::MaterialDefaults>().0.clone();
-
-    let material = loader.load_from_data(
-        mtl::Material {
-            albedo,
-            metallic_roughness,
-            ..mat_defaults
-        },
-        (),
-        &asset_storage,
-    );
-
-    material
+impl Default for InitialVelocity {
+    fn default() -> Self {
+        InitialVelocity {
+            linear: Vector3::zeros(),
+            angular: Vector3::zeros(),
+        }
+    }
 }
 
-fn create_rigid_body(
+pub fn create_rigid_body(
     world: &World,
     transform: &Transform,
     shape: PhysicsShapeTag,
     body_mode: BodyMode,
-    impulse: &Vector3<f32>,
+    material: &PhysicsMaterial,
+    velocity: &InitialVelocity,
 ) -> PhysicsBodyTag {
     let mut rigid_body_server = world.write_resource::<RBodyPhysicsServer<f32>>();
     let mut world_server = world.write_resource::<WorldPhysicsServer<f32>>();
@@ -367,16 +635,28 @@ fn create_rigid_body(
     let desc = RigidBodyDesc {
         mode: body_mode,
         transformation: transform.clone(),
-        mass: 1.0,
+        mass: material.mass,
         shape,
+        friction: material.friction,
+        bounciness: material.restitution,
+        linear_damping: material.linear_damping,
+        angular_damping: material.angular_damping,
+        gravity_scale: material.gravity_scale,
+        ccd_enabled: material.ccd,
     };
 
     let body = rigid_body_server.create_body(*physics_world, &desc);
 
+    // Set now, so the body doesn't spend its first frame at rest.
+    if let BodyMode::Dynamic = body_mode {
+        rigid_body_server.set_linear_velocity(body, &velocity.linear);
+        rigid_body_server.set_angular_velocity(body, &velocity.angular);
+    }
+
     body
 }
 
-fn create_area(
+pub fn create_area(
     world: &World,
     transform: &Transform,
     shape: PhysicsShapeTag) -> PhysicsAreaTag {
@@ -0,0 +1,94 @@
+use crate::{
+    components::*, impulse_system::explosion, physics_cleanup::PendingDeletions,
+    physics_query::PhysicsQuery, transform_utils::*,
+};
+
+use amethyst::{
+    core::{math::Vector3, Transform},
+    ecs::{Entity, Join, Read, ReadExpect, ReadStorage, System, Write, WriteStorage},
+    input::{InputHandler, StringBindings},
+    phythyst::{objects::*, servers::*},
+    renderer::camera::Camera,
+};
+
+/// Max distance reached by the hitscan weapon.
+const HITSCAN_RANGE: f32 = 200.0;
+
+const HITSCAN_BLAST_RADIUS: f32 = 5.0;
+const HITSCAN_BLAST_STRENGTH: f32 = 20.0;
+
+/// What the camera crosshair is pointing at.
+#[derive(Default)]
+pub struct CrosshairTarget {
+    pub entity: Option<Entity>,
+    pub distance: f32,
+}
+
+/// Updates the `CrosshairTarget` and, when the `hitscan` action is pressed,
+/// destroys the bullet under the crosshair and blasts the bodies around the hit point.
+pub struct HitscanSystem {
+    fire_was_down: bool,
+}
+
+impl HitscanSystem {
+    pub fn new() -> Self {
+        HitscanSystem {
+            fire_was_down: false,
+        }
+    }
+}
+
+impl<'s> System<'s> for HitscanSystem {
+    type SystemData = (
+        PhysicsQuery<'s>,
+        Read<'s, InputHandler<StringBindings>>,
+        Write<'s, CrosshairTarget>,
+        Write<'s, PendingDeletions>,
+        ReadExpect<'s, RBodyPhysicsServer<f32>>,
+        ReadStorage<'s, PhysicsBodyTag>,
+        ReadStorage<'s, Camera>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, Bullet>,
+        WriteStorage<'s, PhysicsCommands>,
+    );
+
+    fn run(&mut self, (query, input, mut target, mut pending, body_server, bodies, cameras, transforms, bullets, mut commands): Self::SystemData) {
+        let camera_transform = match (&cameras, &transforms).join().next() {
+            Some((_, transform)) => transform,
+            None => return,
+        };
+
+        let origin = position_f32(camera_transform);
+        let direction = rotation_f32(camera_transform) * -Vector3::z();
+        let hit = query.raycast(&origin, &direction, HITSCAN_RANGE);
+
+        let hit_entity = hit.as_ref().map(|h| h.entity);
+        if hit_entity != target.entity {
+            log::debug!("Crosshair target: {:?}", hit_entity);
+        }
+        target.entity = hit_entity;
+        target.distance = hit.as_ref().map_or(0.0, |h| h.distance);
+
+        let fire_is_down = input.action_is_down("hitscan").unwrap_or(false);
+        if fire_is_down && !self.fire_was_down {
+            if let Some(hit) = hit {
+                if bullets.contains(hit.entity) {
+                    pending.delete(hit.entity);
+                }
+
+                explosion(
+                    &query,
+                    &pending,
+                    &body_server,
+                    &bodies,
+                    &transforms,
+                    &mut commands,
+                    &hit.point,
+                    HITSCAN_BLAST_RADIUS,
+                    HITSCAN_BLAST_STRENGTH,
+                );
+            }
+        }
+        self.fire_was_down = fire_is_down;
+    }
+}
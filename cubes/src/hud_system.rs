@@ -0,0 +1,60 @@
+use crate::{components::SafeZone, game_mode::GameMode};
+
+use amethyst::{
+    ecs::{Join, Read, ReadExpect, ReadStorage, System, WriteStorage},
+    ui::{UiFinder, UiText},
+    utils::fps_counter::FpsCounter,
+};
+
+/// Path of the HUD prefab.
+pub fn hud_prefab_path() -> String {
+    crate::get_dir_path("/ui/hud.ron")
+}
+
+/// Writes the `GameMode`, FPS and safe zone state into the HUD created from `hud_prefab_path`.
+///
+/// The labels are searched each frame, since the HUD is created again by each game.
+#[derive(Default)]
+pub struct HudSystem;
+
+impl<'s> System<'s> for HudSystem {
+    type SystemData = (
+        UiFinder<'s>,
+        Option<ReadExpect<'s, GameMode>>,
+        Read<'s, FpsCounter>,
+        ReadStorage<'s, SafeZone>,
+        WriteStorage<'s, UiText>,
+    );
+
+    fn run(&mut self, (finder, game_mode, fps_counter, safe_zones, mut texts): Self::SystemData) {
+        let mut set_text = |id: &str, text: String| {
+            if let Some(ui_text) = finder.find(id).and_then(|e| texts.get_mut(e)) {
+                ui_text.text = text;
+            }
+        };
+
+        if let Some(game_mode) = game_mode {
+            set_text("score", format!("Score: {}", game_mode.score));
+
+            let mut round = format!("Time: {:.0}", game_mode.round_time_left.ceil());
+            if game_mode.combo > 1 {
+                round += &format!("  Combo x{}", game_mode.combo);
+            }
+            set_text("round", round);
+
+            set_text("ammo", format!("Bullets: {}", game_mode.bullets_left()));
+        }
+
+        let safe_zone_active = (&safe_zones).join().any(|zone| zone.activation_timer > 0.0);
+        set_text(
+            "safe_zone",
+            if safe_zone_active {
+                "Safe zone ACTIVE".to_string()
+            } else {
+                String::new()
+            },
+        );
+
+        set_text("fps", format!("{:.0} FPS", fps_counter.sampled_fps()));
+    }
+}
@@ -1,22 +1,26 @@
-use crate::components::*;
+use crate::{
+    components::*, physics_cleanup::PendingDeletions, physics_query::PhysicsQuery,
+    transform_utils::*,
+};
 
 use amethyst::{
-    core::Time,
+    core::{
+        math::{Point3, Vector3},
+        Transform,
+    },
     ecs::{
         Join, System, Entities, Entity, ReadStorage, WriteStorage, ReadExpect,
     },
     phythyst::{
         servers::*,
         objects::*,
+        PhysicsTime,
     },
-    assets::{
-        Handle,
-    },
-    renderer::{
-        mtl::Material,
-    }
 };
 
+/// Executes the `PhysicalImpulse` and the one shot `PhysicsCommands` of the entities,
+/// once per frame right before the stepping.
+/// The forces and torques are left in the queue for the `ForceSystem`.
 pub struct ImpulseSystem{
 
 }
@@ -35,9 +39,10 @@ impl<'s> System<'s> for ImpulseSystem {
         ReadExpect<'s, RBodyPhysicsServer<f32>>,
         ReadStorage<'s, PhysicsBodyTag>,
         WriteStorage<'s, PhysicalImpulse>,
+        WriteStorage<'s, PhysicsCommands>,
     );
 
-    fn run(&mut self, (entities, body_server, bodies, mut impulses): Self::SystemData) {
+    fn run(&mut self, (entities, body_server, bodies, mut impulses, mut commands): Self::SystemData) {
 
         let mut entities_with_impulse = Vec::<Entity>::new();
         for (entity, body, impulse) in (&*entities, &bodies, &impulses).join() {
@@ -51,5 +56,145 @@ impl<'s> System<'s> for ImpulseSystem {
 
             impulses.remove(*e);
         }
+
+        let mut entities_done = Vec::<Entity>::new();
+        for (entity, body, body_commands) in (&*entities, &bodies, &mut commands).join() {
+
+            let pending = std::mem::replace(&mut body_commands.commands, Vec::new());
+            for command in pending {
+                match command {
+                    PhysicsCommand::Impulse(impulse) => {
+                        body_server.apply_impulse(*body, &impulse);
+                    }
+                    PhysicsCommand::ImpulseAtPoint { impulse, point } => {
+                        body_server.apply_impulse_at_position(*body, &impulse, &point);
+                    }
+                    PhysicsCommand::AngularImpulse(impulse) => {
+                        body_server.apply_angular_impulse(*body, &impulse);
+                    }
+                    PhysicsCommand::SetLinearVelocity(velocity) => {
+                        body_server.set_linear_velocity(*body, &velocity);
+                    }
+                    PhysicsCommand::SetAngularVelocity(velocity) => {
+                        body_server.set_angular_velocity(*body, &velocity);
+                    }
+                    PhysicsCommand::Force { .. } | PhysicsCommand::Torque { .. } => {
+                        body_commands.push(command);
+                    }
+                }
+            }
+
+            if body_commands.commands.is_empty() {
+                entities_done.push(entity);
+            }
+        }
+
+        for e in entities_done.iter() {
+
+            commands.remove(*e);
+        }
+    }
+}
+
+/// Applies the forces and torques of the `PhysicsCommands` through the force API of
+/// the body, and counts down their duration in physics time.
+///
+/// #IMPORTANT
+/// It runs at each physics step, see `PhysicsBundle::with_in_physics`.
+#[derive(Default)]
+pub struct ForceSystem;
+
+impl<'s> System<'s> for ForceSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadExpect<'s, PhysicsTime>,
+        ReadExpect<'s, RBodyPhysicsServer<f32>>,
+        ReadStorage<'s, PhysicsBodyTag>,
+        WriteStorage<'s, PhysicsCommands>,
+    );
+
+    fn run(&mut self, (entities, physics_time, body_server, bodies, mut commands): Self::SystemData) {
+        let step_delta = physics_time.delta_seconds();
+
+        let mut entities_done = Vec::<Entity>::new();
+        for (entity, body, body_commands) in (&*entities, &bodies, &mut commands).join() {
+            for command in body_commands.commands.iter_mut() {
+                match command {
+                    PhysicsCommand::Force { force, duration } => {
+                        body_server.apply_force(*body, force);
+                        *duration -= step_delta;
+                    }
+                    PhysicsCommand::Torque { torque, duration } => {
+                        body_server.apply_torque(*body, torque);
+                        *duration -= step_delta;
+                    }
+                    _ => {}
+                }
+            }
+
+            body_commands.commands.retain(|command| match command {
+                PhysicsCommand::Force { duration, .. } | PhysicsCommand::Torque { duration, .. } => {
+                    *duration > 0.0
+                }
+                _ => true,
+            });
+
+            if body_commands.commands.is_empty() {
+                entities_done.push(entity);
+            }
+        }
+
+        for e in entities_done.iter() {
+            commands.remove(*e);
+        }
+    }
+}
+
+/// Pushes an impulse, pointing away from `center`, to every dynamic body within `radius`;
+/// the entities about to be deleted are skipped.
+/// The impulse fades linearly from `strength`, at the center, to zero at `radius`.
+pub fn explosion(
+    query: &PhysicsQuery,
+    pending: &PendingDeletions,
+    body_server: &RBodyPhysicsServer<f32>,
+    bodies: &ReadStorage<PhysicsBodyTag>,
+    transforms: &ReadStorage<Transform>,
+    commands: &mut WriteStorage<PhysicsCommands>,
+    center: &Point3<f32>,
+    radius: f32,
+    strength: f32,
+) {
+    for entity in query.overlap_sphere(center, radius) {
+        if !query.entities().is_alive(entity) || pending.contains(entity) {
+            continue;
+        }
+        match bodies.get(entity) {
+            Some(body) if body_server.mode(*body) == BodyMode::Dynamic => {}
+            _ => continue,
+        }
+
+        let position = match transforms.get(entity) {
+            Some(transform) => position_f32(transform),
+            None => continue,
+        };
+
+        let offset = position - center;
+        let distance = offset.norm();
+        if distance >= radius {
+            continue;
+        }
+
+        let direction = if distance > 0.0 {
+            offset / distance
+        } else {
+            Vector3::y()
+        };
+        let falloff = 1.0 - distance / radius;
+
+        if let Ok(entry) = commands.entry(entity) {
+            entry
+                .or_insert_with(PhysicsCommands::new)
+                .push(PhysicsCommand::Impulse(direction * strength * falloff));
+        }
     }
 }
@@ -0,0 +1,70 @@
+use crate::{
+    collider::{ColliderDesc, ShapeOffset},
+    components::PhysicsModifier,
+    lighting::LightingDesc,
+    physics_material::PhysicsMaterial,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// An obstacle of the arena.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ObstacleDesc {
+    pub collider: ColliderDesc,
+    #[serde(default)]
+    pub transform: ShapeOffset,
+    #[serde(default = "default_obstacle_color")]
+    pub color: [f32; 4],
+    /// Name of a material of the `MaterialLibrary`; `color` is used without it.
+    #[serde(default)]
+    pub render_material: Option<String>,
+    #[serde(default)]
+    pub material: PhysicsMaterial,
+    /// Hitting a target with a bullet gives points.
+    #[serde(default)]
+    pub target: bool,
+    /// Triangle meshes can't be dynamic.
+    #[serde(default)]
+    pub dynamic: bool,
+}
+
+fn default_obstacle_color() -> [f32; 4] {
+    [0.0, 1.0, 0.0, 1.0]
+}
+
+/// An area that changes the physics of the bodies inside it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ZoneDesc {
+    pub shape: ColliderDesc,
+    #[serde(default)]
+    pub transform: ShapeOffset,
+    pub modifier: PhysicsModifier,
+}
+
+/// The content of a level file, loaded from `game_directory/levels`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LevelDesc {
+    #[serde(default)]
+    pub obstacles: Vec<ObstacleDesc>,
+    #[serde(default)]
+    pub zones: Vec<ZoneDesc>,
+    /// Without it, the level is lit by a single directional light.
+    #[serde(default)]
+    pub lighting: LightingDesc,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_file_round_trips() {
+        let data = std::fs::read_to_string(crate::get_dir_path("/levels/arena.ron")).unwrap();
+        let level: LevelDesc = ron::de::from_str(&data).unwrap();
+        assert!(!level.obstacles.is_empty());
+
+        let data = ron::ser::to_string(&level).unwrap();
+        let reloaded: LevelDesc = ron::de::from_str(&data).unwrap();
+        assert_eq!(format!("{:?}", level), format!("{:?}", reloaded));
+    }
+}
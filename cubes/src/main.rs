@@ -1,23 +1,59 @@
+mod audio;
+#[cfg(test)]
+mod ccd_scenario;
+mod collider;
+mod game_mode;
 mod game_state;
 mod render_graph;
 mod components;
+mod contact_event_system;
+mod hitscan_system;
+mod hud_system;
 mod impulse_system;
 mod safe_zone_system;
+mod snapshot;
 mod target_system;
+mod physics_cleanup;
+mod physics_debug_system;
+mod physics_material;
+mod physics_modifier_system;
+mod physics_query;
+mod physics_rate_system;
+mod physics_stats;
+mod physics_tag_index;
+mod platform;
+mod results_state;
+mod level;
+#[path = "../../common/lighting.rs"]
+mod lighting;
+mod material_animation_system;
+mod material_library;
+mod particle_system;
+mod menu;
+mod menu_state;
+mod pause_state;
+mod settings_state;
+mod transform_utils;
 
 use amethyst::{
     amethyst_nphysics,
     assets::Processor,
+    audio::Source,
     core::{frame_limiter::FrameRateLimitStrategy, transform::TransformBundle, Float},
     input::{InputBundle, StringBindings},
-    phythyst::{PhysicsBundle, PhysicsTime},
-    prelude::{Application, Config, GameDataBuilder},
-    renderer::{sprite::SpriteSheet, types::DefaultBackend, RenderingSystem, visibility::VisibilitySortingSystem},
+    phythyst::PhysicsBundle,
+    prelude::{Application, Config, GameDataBuilder, SimpleState},
+    renderer::{
+        debug_drawing::{DebugLines, DebugLinesParams},
+        sprite::SpriteSheet, types::DefaultBackend, RenderingSystem, visibility::VisibilitySortingSystem,
+    },
     ui::{DrawUiDesc, UiBundle},
-    utils::application_root_dir,
+    utils::{application_root_dir, fps_counter::FpsCounterBundle},
     window::{DisplayConfig, WindowBundle},
 };
 
+use audio::{AudioBackend, AudioSettings, AudioSystem, SoundsConfig};
+use physics_rate_system::{PhysicsRate, PhysicsRateConfig, PhysicsRateSystem};
 use render_graph::MyRenderGraphCreator;
 use std::string::String;
 
@@ -52,12 +88,49 @@ fn main() -> amethyst::Result<()> {
     let game_data = setup_physics(game_data);
     let game_data = setup_fake_physics_systems(game_data);
     let game_data = setup_transforms(game_data);
+    let game_data = setup_ui(game_data);
     let game_data = setup_render_graph_constructor(game_data);
 
-    let mut game = Application::build("./", game_state::CubeGameState::new())?
+    // `--snapshot <path>` starts the game from a captured moment, skipping the menu.
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(i) = args.iter().position(|arg| arg == "--snapshot") {
+        let path = args
+            .get(i + 1)
+            .ok_or_else(|| amethyst::Error::from_string("The snapshot path is missing."))?;
+        let initial_state = game_state::CubeGameState::new()
+            .with_snapshot(snapshot::PhysicsSnapshot::load(path)?);
+        run_game(initial_state, game_data)
+    } else {
+        run_game(menu_state::MainMenuState::new(), game_data)
+    }
+}
+
+fn run_game<'a, 'b, S: SimpleState + 'a>(
+    initial_state: S,
+    game_data: GameDataBuilder<'a, 'b>,
+) -> amethyst::Result<()> {
+    let physics_rate_config =
+        PhysicsRateConfig::load(get_dir_path("/configs/physics_conf.ron"));
+
+    // `--no-audio` keeps the game silent, even when an audio device is available.
+    let audio_backend = if std::env::args().any(|arg| arg == "--no-audio") {
+        AudioBackend::Null
+    } else {
+        AudioBackend::Device
+    };
+
+    let mut game = Application::build("./", initial_state)?
         .with_frame_limit(FrameRateLimitStrategy::Unlimited, 1000)
         .with_physics(amethyst_nphysics::create_physics::<f32>())
-        .with_resource(PhysicsTime::default().set_frames_per_second(240)) // optional
+        .with_resource(physics_rate_config.physics_time(physics_rate_config.frames_per_second))
+        .with_resource(PhysicsRate {
+            frames_per_second: physics_rate_config.frames_per_second,
+        })
+        .with_resource(physics_rate_config)
+        .with_resource(DebugLines::new())
+        .with_resource(DebugLinesParams { line_width: 2.0 })
+        .with_resource(audio_backend)
+        .with_resource(AudioSettings::load(AudioSettings::path()))
         .build(game_data)?;
 
     game.run();
@@ -74,12 +147,33 @@ fn get_dir_path(path: &str) -> String {
 
 #[inline]
 fn setup_physics<'a, 'b>(gdb: GameDataBuilder<'a, 'b>) -> GameDataBuilder<'a, 'b> {
-    gdb.with_bundle(PhysicsBundle::new()).unwrap()
+    // The barriers isolate the stepping, so the stats measure only the physics.
+    gdb.with_barrier()
+        .with(impulse_system::ImpulseSystem::new(), "impulse_system", &[])
+        .with(physics_stats::PhysicsStatsBeginSystem, "physics_stats_begin_system", &["impulse_system"])
+        .with_barrier()
+        .with_bundle(
+            PhysicsBundle::new()
+                .with_in_physics(physics_stats::PhysicsStepCounterSystem, "physics_step_counter_system", &[])
+                .with_in_physics(impulse_system::ForceSystem, "force_system", &[])
+                .with_in_physics(
+                    physics_modifier_system::PhysicsModifierSystem::default(),
+                    "physics_modifier_system",
+                    &[],
+                ),
+        )
+        .unwrap()
 }
 
 #[inline]
 fn setup_gameplay_systems<'a, 'b>(gdb: GameDataBuilder<'a, 'b>) -> GameDataBuilder<'a, 'b> {
-    gdb.with(impulse_system::ImpulseSystem::new(), "impulse_system", &[])
+    gdb.with(physics_debug_system::PhysicsDebugSystem::new(), "physics_debug_system", &[])
+        .with(hitscan_system::HitscanSystem::new(), "hitscan_system", &[])
+        .with(physics_stats::PhysicsStatsOverlaySystem::new(), "physics_stats_overlay_system", &[])
+        .with(game_mode::GameModeSystem, "game_mode_system", &[])
+        .with(hud_system::HudSystem::default(), "hud_system", &["game_mode_system"])
+        .with(Processor::<Source>::new(), "source_processor", &[])
+        .with(particle_system::ParticleSystem, "particle_system", &[])
 }
 
 #[inline]
@@ -88,7 +182,23 @@ fn setup_fake_physics_systems<'a, 'b>(gdb: GameDataBuilder<'a, 'b>) -> GameDataB
     // I'm setting here because substepping is not yet implemented.
     // The barrier is used to execute this systems always after the stepping and never before. But again only because is not a subscript
     gdb.with_barrier()
+        .with(physics_stats::PhysicsStatsEndSystem, "physics_stats_end_system", &[])
+        .with(PhysicsRateSystem, "physics_rate_system", &["physics_stats_end_system"])
         .with(safe_zone_system::SafeZoneSystem::new(), "safe_zone_system", &[])
+        .with(contact_event_system::ContactEventSystem::default(), "contact_event_system", &[])
+        .with(target_system::TargetSystem::new(), "target_system", &["contact_event_system"])
+        .with(particle_system::ImpactParticlesSystem::new(), "impact_particles_system", &["contact_event_system"])
+        .with(material_animation_system::MaterialAnimationSystem, "material_animation_system", &["safe_zone_system"])
+        .with(
+            AudioSystem::new(SoundsConfig::load(get_dir_path("/configs/sounds_conf.ron"))),
+            "audio_system",
+            &["safe_zone_system", "contact_event_system"],
+        )
+        .with(
+            physics_cleanup::PhysicsCleanupSystem,
+            "physics_cleanup_system",
+            &["safe_zone_system", "target_system", "impact_particles_system", "audio_system"],
+        )
 }
 
 #[inline]
@@ -117,8 +227,15 @@ fn setup_transforms<'a, 'b>(gdb: GameDataBuilder<'a, 'b>) -> GameDataBuilder<'a,
 fn setup_inputs<'a, 'b>(gdb: GameDataBuilder<'a, 'b>) -> GameDataBuilder<'a, 'b> {
     gdb.with_bundle(
         InputBundle::<StringBindings>::new()
-            .with_bindings_from_file("res/bindings_config.ron")
-            .unwrap(),
+            .with_bindings(settings_state::UserBindings::load_bindings()),
     )
     .unwrap()
 }
+
+#[inline]
+fn setup_ui<'a, 'b>(gdb: GameDataBuilder<'a, 'b>) -> GameDataBuilder<'a, 'b> {
+    gdb.with_bundle(UiBundle::<DefaultBackend, StringBindings>::new())
+        .unwrap()
+        .with_bundle(FpsCounterBundle::default())
+        .unwrap()
+}
@@ -0,0 +1,54 @@
+use crate::components::MaterialAnimation;
+
+use amethyst::{
+    core::Time,
+    ecs::{Join, ReadExpect, System, WriteStorage},
+    renderer::resources::Tint,
+};
+
+/// Changes smaller than this are not written.
+const TINT_EPSILON: f32 = 1.0 / 512.0;
+
+/// Advances the `MaterialAnimation`s and writes their `Tint`.
+///
+/// Only the tint changes, never the material handle, and it's written only when
+/// the color actually differs, so idle entities are left untouched.
+#[derive(Default)]
+pub struct MaterialAnimationSystem;
+
+impl<'s> System<'s> for MaterialAnimationSystem {
+    type SystemData = (
+        ReadExpect<'s, Time>,
+        WriteStorage<'s, MaterialAnimation>,
+        WriteStorage<'s, Tint>,
+    );
+
+    fn run(&mut self, (time, mut animations, mut tints): Self::SystemData) {
+        let delta = time.delta_seconds();
+
+        for (animation, tint) in (&mut animations, &mut tints.restrict_mut()).join() {
+            if animation.active {
+                animation.blend = (animation.blend + delta / animation.fade_in.max(delta)).min(1.0);
+            } else {
+                animation.blend = (animation.blend - delta / animation.fade_out.max(delta)).max(0.0);
+            }
+
+            if animation.blend > 0.0 {
+                animation.pulse_time += delta;
+            } else {
+                animation.pulse_time = 0.0;
+            }
+
+            let new_tint = animation.tint();
+            let current = tint.get_unchecked().0;
+            let changed = (current.red - new_tint.0.red).abs() > TINT_EPSILON
+                || (current.green - new_tint.0.green).abs() > TINT_EPSILON
+                || (current.blue - new_tint.0.blue).abs() > TINT_EPSILON
+                || (current.alpha - new_tint.0.alpha).abs() > TINT_EPSILON;
+
+            if changed {
+                *tint.get_mut_unchecked() = new_tint;
+            }
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use amethyst::{
+    assets::{AssetStorage, Handle, Loader},
+    config::Config,
+    ecs::prelude::World,
+    renderer::{
+        mtl,
+        palette::LinSrgba,
+        rendy::texture::{
+            self,
+            image::{ImageTextureConfig, Repr},
+        },
+        types, ImageFormat,
+    },
+};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+
+/// A named material of the `MaterialLibrary`.
+///
+/// Each texture is a path relative to the game directory; when a texture is not set
+/// the solid colors are used instead.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaterialDesc {
+    /// Albedo used when `albedo` is not set.
+    pub color: [f32; 4],
+    pub metallic: f32,
+    pub roughness: f32,
+    /// Emission used when `emission` is not set.
+    pub emission_color: [f32; 3],
+    pub albedo: Option<String>,
+    pub normal: Option<String>,
+    /// Roughness in the green channel and metallic in the blue one, like glTF.
+    pub metallic_roughness: Option<String>,
+    pub emission: Option<String>,
+}
+
+impl Default for MaterialDesc {
+    fn default() -> Self {
+        MaterialDesc {
+            color: [1.0, 1.0, 1.0, 1.0],
+            metallic: 0.0,
+            roughness: 0.5,
+            emission_color: [0.0, 0.0, 0.0],
+            albedo: None,
+            normal: None,
+            metallic_roughness: None,
+            emission: None,
+        }
+    }
+}
+
+/// The materials that the spawn functions can refer to by name,
+/// loaded from `configs/materials.ron`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaterialLibrary {
+    pub materials: HashMap<String, MaterialDesc>,
+    /// The materials already created, so the textures are loaded once.
+    #[serde(skip)]
+    handles: HashMap<String, Handle<mtl::Material>>,
+}
+
+impl MaterialLibrary {
+    pub fn path() -> String {
+        crate::get_dir_path("/configs/materials.ron")
+    }
+}
+
+/// Returns the material called `name` in the `MaterialLibrary`, or `None` if there
+/// is no such material.
+pub fn named_material(world: &World, name: &str) -> Option<Handle<mtl::Material>> {
+    let desc = {
+        let library = world.read_resource::<MaterialLibrary>();
+        if let Some(handle) = library.handles.get(name) {
+            return Some(handle.clone());
+        }
+        library.materials.get(name).cloned()
+    };
+
+    let desc = match desc {
+        Some(desc) => desc,
+        None => {
+            log::warn!("The material {} is not in {}", name, MaterialLibrary::path());
+            return None;
+        }
+    };
+
+    let handle = create_library_material(world, &desc);
+    world
+        .write_resource::<MaterialLibrary>()
+        .handles
+        .insert(name.to_string(), handle.clone());
+    Some(handle)
+}
+
+/// Returns the named material if there is one, otherwise a solid color material.
+pub fn material_or_color(
+    world: &World,
+    name: Option<&str>,
+    color: LinSrgba,
+    metallic: f32,
+    roughness: f32,
+) -> Handle<mtl::Material> {
+    name.and_then(|name| named_material(world, name))
+        .unwrap_or_else(|| create_material(world, color, metallic, roughness))
+}
+
+fn create_library_material(world: &World, desc: &MaterialDesc) -> Handle<mtl::Material> {
+    let loader = world.read_resource::<Loader>();
+    let texture_storage = world.read_resource::<AssetStorage<types::Texture>>();
+
+    let load_texture = |file: &str, format: ImageFormat| -> Handle<types::Texture> {
+        loader.load(crate::get_dir_path(&format!("/{}", file)), format, (), &texture_storage)
+    };
+    let load_color = |color: LinSrgba| -> Handle<types::Texture> {
+        loader.load_from_data(
+            texture::palette::load_from_linear_rgba(color).into(),
+            (),
+            &texture_storage,
+        )
+    };
+
+    let [r, g, b, a] = desc.color;
+    let albedo = match &desc.albedo {
+        Some(file) => load_texture(file, ImageFormat::default()),
+        None => load_color(LinSrgba::new(r, g, b, a)),
+    };
+
+    let metallic_roughness = match &desc.metallic_roughness {
+        Some(file) => load_texture(file, linear_image_format()),
+        None => load_color(LinSrgba::new(0.0, desc.roughness, desc.metallic, 0.0)),
+    };
+
+    let [r, g, b] = desc.emission_color;
+    let emission = match &desc.emission {
+        Some(file) => load_texture(file, ImageFormat::default()),
+        None => load_color(LinSrgba::new(r, g, b, 1.0)),
+    };
+
+    let mat_defaults = world.read_resource::<mtl::MaterialDefaults>().0.clone();
+    let normal = match &desc.normal {
+        Some(file) => load_texture(file, linear_image_format()),
+        None => mat_defaults.normal.clone(),
+    };
+
+    let material_storage = world.read_resource::<AssetStorage<mtl::Material>>();
+    loader.load_from_data(
+        mtl::Material {
+            albedo,
+            metallic_roughness,
+            emission,
+            normal,
+            ..mat_defaults
+        },
+        (),
+        &material_storage,
+    )
+}
+
+/// Normal and metallic roughness maps hold data, not colors, so they are not sRGB.
+fn linear_image_format() -> ImageFormat {
+    ImageFormat(ImageTextureConfig {
+        repr: Repr::Unorm,
+        ..Default::default()
+    })
+}
+
+/// Creates a material with solid colors.
+fn create_material(
+    world: &World,
+    color: LinSrgba,
+    metallic: f32,
+    roughness: f32,
+) -> Handle<mtl::Material> {
+    let loader = world.read_resource::<Loader>();
+
+    // Material creation
+    let asset_storage = world.read_resource::<AssetStorage<types::Texture>>();
+    let albedo = loader.load_from_data(
+        texture::palette::load_from_linear_rgba(color).into(),
+        (),
+        &asset_storage,
+    );
+
+    let metallic_roughness = loader.load_from_data(
+        texture::palette::load_from_linear_rgba(LinSrgba::new(0.0, roughness, metallic, 0.0))
+            .into(),
+        (),
+        &asset_storage,
+    );
+
+    let asset_storage = world.read_resource::<AssetStorage<mtl::Material>>();
+    let mat_defaults = world.read_resource::<mtl::MaterialDefaults>().0.clone();
+
+    let material = loader.load_from_data(
+        mtl::Material {
+            albedo,
+            metallic_roughness,
+            ..mat_defaults
+        },
+        (),
+        &asset_storage,
+    );
+
+    material
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn material_library_round_trips() {
+        let data = std::fs::read_to_string(crate::get_dir_path("/configs/materials.ron")).unwrap();
+        let library: MaterialLibrary = ron::de::from_str(&data).unwrap();
+        assert!(!library.materials.is_empty());
+
+        let data = ron::ser::to_string(&library).unwrap();
+        let reloaded: MaterialLibrary = ron::de::from_str(&data).unwrap();
+        assert_eq!(library.materials.len(), reloaded.materials.len());
+        for (name, desc) in library.materials.iter() {
+            assert_eq!(format!("{:?}", desc), format!("{:?}", reloaded.materials[name]));
+        }
+    }
+
+    #[test]
+    fn missing_fields_take_the_defaults() {
+        let desc: MaterialDesc = ron::de::from_str("(metallic: 1.0)").unwrap();
+        assert_eq!(desc.metallic, 1.0);
+        assert_eq!(desc.color, MaterialDesc::default().color);
+        assert_eq!(desc.roughness, MaterialDesc::default().roughness);
+        assert!(desc.albedo.is_none());
+    }
+}
@@ -0,0 +1,105 @@
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    ecs::{prelude::World, Entity},
+    prelude::Builder,
+    ui::{
+        get_default_font, Anchor, FontAsset, FontHandle, Interactable, UiEvent, UiEventType,
+        UiText, UiTransform,
+    },
+    StateEvent,
+};
+
+const ITEM_HEIGHT: f32 = 40.0;
+
+/// A column of clickable labels, used by the menu states.
+///
+/// The entities are owned by the menu, so call `clear` when the state stops.
+pub struct Menu {
+    font: FontHandle,
+    title: Option<Entity>,
+    items: Vec<Entity>,
+}
+
+impl Menu {
+    pub fn new(world: &mut World, title: &str, items: &[String]) -> Self {
+        let font = {
+            let loader = world.read_resource::<Loader>();
+            let font_storage = world.read_resource::<AssetStorage<FontAsset>>();
+            get_default_font(&loader, &font_storage)
+        };
+
+        let mut menu = Menu {
+            font,
+            title: None,
+            items: Vec::new(),
+        };
+
+        let top = (items.len() as f32 + 1.0) * ITEM_HEIGHT * 0.5;
+
+        menu.title = Some(menu.create_label(world, "menu_title", title, 28.0, top, false));
+        for (i, item) in items.iter().enumerate() {
+            let y = top - (i as f32 + 1.5) * ITEM_HEIGHT;
+            let id = format!("menu_item_{}", i);
+            let entity = menu.create_label(world, &id, item, 20.0, y, true);
+            menu.items.push(entity);
+        }
+
+        menu
+    }
+
+    fn create_label(
+        &self,
+        world: &mut World,
+        id: &str,
+        text: &str,
+        font_size: f32,
+        y: f32,
+        interactable: bool,
+    ) -> Entity {
+        let mut builder = world
+            .create_entity()
+            .with(UiTransform::new(
+                id.to_string(),
+                Anchor::Middle,
+                Anchor::Middle,
+                0.0,
+                y,
+                1.0,
+                500.0,
+                ITEM_HEIGHT,
+            ))
+            .with(UiText::new(
+                self.font.clone(),
+                text.to_string(),
+                [1.0, 1.0, 1.0, 1.0],
+                font_size,
+            ));
+        if interactable {
+            builder = builder.with(Interactable);
+        }
+        builder.build()
+    }
+
+    /// Returns the index of the item clicked in this event.
+    pub fn clicked_item(&self, event: &StateEvent) -> Option<usize> {
+        match event {
+            StateEvent::Ui(UiEvent {
+                event_type: UiEventType::Click,
+                target,
+            }) => self.items.iter().position(|item| item == target),
+            _ => None,
+        }
+    }
+
+    pub fn set_item_text(&self, world: &mut World, index: usize, text: String) {
+        if let Some(ui_text) = world.write_storage::<UiText>().get_mut(self.items[index]) {
+            ui_text.text = text;
+        }
+    }
+
+    pub fn clear(&mut self, world: &mut World) {
+        let mut entities: Vec<Entity> = self.items.drain(..).collect();
+        entities.extend(self.title.take());
+        world.delete_entities(&entities).unwrap();
+    }
+}
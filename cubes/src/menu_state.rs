@@ -0,0 +1,131 @@
+use crate::{game_state::CubeGameState, menu::Menu, settings_state::SettingsState};
+
+use amethyst::{
+    prelude::{GameData, SimpleState, SimpleTrans, StateData, Trans},
+    StateEvent,
+};
+
+use std::{fs, path::PathBuf};
+
+/// Returns the level files found in `game_directory/levels`.
+pub fn available_levels() -> Vec<PathBuf> {
+    let mut levels: Vec<PathBuf> = fs::read_dir(crate::get_dir_path("/levels"))
+        .map(|dir| {
+            dir.filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.extension().map_or(false, |ext| ext == "ron"))
+                .collect()
+        })
+        .unwrap_or_default();
+    levels.sort();
+    levels
+}
+
+fn level_name(path: &PathBuf) -> String {
+    path.file_stem()
+        .map(|name| name.to_string_lossy().replace('_', " "))
+        .unwrap_or_default()
+}
+
+enum MainMenuPage {
+    Main,
+    LevelSelect,
+}
+
+/// The first state of the game.
+pub struct MainMenuState {
+    menu: Option<Menu>,
+    page: MainMenuPage,
+    levels: Vec<PathBuf>,
+    selected_level: usize,
+}
+
+impl MainMenuState {
+    pub fn new() -> Self {
+        MainMenuState {
+            menu: None,
+            page: MainMenuPage::Main,
+            levels: Vec::new(),
+            selected_level: 0,
+        }
+    }
+
+    fn show(&mut self, data: &mut StateData<'_, GameData<'_, '_>>, page: MainMenuPage) {
+        if let Some(mut menu) = self.menu.take() {
+            menu.clear(data.world);
+        }
+
+        let menu = match page {
+            MainMenuPage::Main => {
+                let level = self
+                    .levels
+                    .get(self.selected_level)
+                    .map(level_name)
+                    .unwrap_or_default();
+                Menu::new(
+                    data.world,
+                    "The cubes",
+                    &[
+                        "Play".to_string(),
+                        format!("Level: {}", level),
+                        "Settings".to_string(),
+                        "Quit".to_string(),
+                    ],
+                )
+            }
+            MainMenuPage::LevelSelect => {
+                let mut items: Vec<String> = self.levels.iter().map(level_name).collect();
+                items.push("Back".to_string());
+                Menu::new(data.world, "Select level", &items)
+            }
+        };
+
+        self.menu = Some(menu);
+        self.page = page;
+    }
+}
+
+impl SimpleState for MainMenuState {
+    fn on_start(&mut self, mut data: StateData<'_, GameData<'_, '_>>) {
+        self.levels = available_levels();
+        self.show(&mut data, MainMenuPage::Main);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        if let Some(mut menu) = self.menu.take() {
+            menu.clear(data.world);
+        }
+    }
+
+    fn handle_event(
+        &mut self,
+        mut data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        let clicked = match self.menu.as_ref().and_then(|menu| menu.clicked_item(&event)) {
+            Some(clicked) => clicked,
+            None => return Trans::None,
+        };
+
+        match self.page {
+            MainMenuPage::Main => match clicked {
+                0 => match self.levels.get(self.selected_level) {
+                    Some(level) => {
+                        return Trans::Switch(Box::new(CubeGameState::new().with_level(level.clone())))
+                    }
+                    None => log::error!("No level found in {}", crate::get_dir_path("/levels")),
+                },
+                1 => self.show(&mut data, MainMenuPage::LevelSelect),
+                2 => return Trans::Switch(Box::new(SettingsState::new())),
+                _ => return Trans::Quit,
+            },
+            MainMenuPage::LevelSelect => {
+                if clicked < self.levels.len() {
+                    self.selected_level = clicked;
+                }
+                self.show(&mut data, MainMenuPage::Main);
+            }
+        }
+
+        Trans::None
+    }
+}
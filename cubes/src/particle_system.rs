@@ -0,0 +1,214 @@
+use crate::{
+    components::*,
+    contact_event_system::{ContactEvent, ContactEventKind},
+    transform_utils::*,
+};
+
+use amethyst::{
+    assets::Handle,
+    core::{
+        math::Vector3,
+        shrev::{EventChannel, ReaderId},
+        Float, Time, Transform,
+    },
+    ecs::{
+        Entities, Entity, Join, LazyUpdate, Read, ReadExpect, ReadStorage, Resources, System,
+        SystemData, WriteStorage,
+    },
+    renderer::{mtl::Material, palette::Srgba, resources::Tint, types::Mesh, Transparent},
+};
+
+use rand::prelude::*;
+
+/// Spawns the particles of the `ParticleEmitter`s, then moves, fades and deletes them.
+///
+/// The particles are drawn by the transparent PBR pass, with the `ParticleAssets`.
+#[derive(Default)]
+pub struct ParticleSystem;
+
+impl<'s> System<'s> for ParticleSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadExpect<'s, Time>,
+        Option<ReadExpect<'s, ParticleAssets>>,
+        WriteStorage<'s, ParticleEmitter>,
+        WriteStorage<'s, Particle>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, Tint>,
+        WriteStorage<'s, Handle<Mesh>>,
+        WriteStorage<'s, Handle<Material>>,
+        WriteStorage<'s, Transparent>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, time, assets, mut emitters, mut particles, mut transforms, mut tints, mut meshes, mut materials, mut transparents): Self::SystemData,
+    ) {
+        let delta = time.delta_seconds();
+
+        for (entity, particle, transform, tint) in
+            (&*entities, &mut particles, &mut transforms, &mut tints).join()
+        {
+            particle.age += delta;
+            if particle.age >= particle.lifetime {
+                entities.delete(entity).unwrap();
+                continue;
+            }
+
+            particle.velocity += particle.gravity * delta;
+            let position = translation_f32(transform) + particle.velocity * delta;
+            transform.set_translation_xyz(position.x, position.y, position.z);
+
+            let t = particle.age / particle.lifetime;
+            let size = lerp(particle.start_size, particle.end_size, t);
+            *transform.scale_mut() = Vector3::repeat(Float::from(size));
+            *tint = color_tint(&particle.start_color, &particle.end_color, t);
+        }
+
+        let assets = match assets {
+            Some(assets) => assets,
+            None => return,
+        };
+
+        let mut rng = thread_rng();
+        let mut spawns = Vec::<(Vector3<f32>, Particle)>::new();
+        let mut finished = Vec::<Entity>::new();
+
+        for (entity, emitter, transform) in (&*entities, &mut emitters, &transforms).join() {
+            let count = match emitter.mode {
+                EmitterMode::Burst { count } => {
+                    finished.push(entity);
+                    count
+                }
+                EmitterMode::Continuous { rate } => {
+                    emitter.spawn_accumulator += rate * delta;
+                    let count = emitter.spawn_accumulator.floor();
+                    emitter.spawn_accumulator -= count;
+                    count as usize
+                }
+            };
+
+            let origin = translation_f32(transform);
+            for _ in 0..count {
+                spawns.push((origin, spawn_particle(emitter, &mut rng)));
+            }
+        }
+
+        for entity in finished {
+            entities.delete(entity).unwrap();
+        }
+
+        for (position, particle) in spawns {
+            let mut transform = Transform::default();
+            transform.set_translation_xyz(position.x, position.y, position.z);
+            *transform.scale_mut() = Vector3::repeat(Float::from(particle.start_size));
+            let tint = color_tint(&particle.start_color, &particle.end_color, 0.0);
+
+            entities
+                .build_entity()
+                .with(transform, &mut transforms)
+                .with(tint, &mut tints)
+                .with(assets.mesh.clone(), &mut meshes)
+                .with(assets.material.clone(), &mut materials)
+                .with(Transparent::default(), &mut transparents)
+                .with(particle, &mut particles)
+                .build();
+        }
+    }
+}
+
+/// Spawns an impact burst where a bullet hits something that isn't a bullet.
+pub struct ImpactParticlesSystem {
+    contact_reader: Option<ReaderId<ContactEvent>>,
+}
+
+impl ImpactParticlesSystem {
+    pub fn new() -> Self {
+        ImpactParticlesSystem {
+            contact_reader: None,
+        }
+    }
+}
+
+/// Contacts weaker than this don't spawn particles, so rolling bullets stay quiet.
+const MIN_IMPACT_IMPULSE: f32 = 2.0;
+
+impl<'s> System<'s> for ImpactParticlesSystem {
+    type SystemData = (
+        Entities<'s>,
+        Read<'s, EventChannel<ContactEvent>>,
+        Read<'s, LazyUpdate>,
+        ReadStorage<'s, Bullet>,
+    );
+
+    fn run(&mut self, (entities, contacts, lazy, bullets): Self::SystemData) {
+        for event in contacts.read(self.contact_reader.as_mut().unwrap()) {
+            if event.kind != ContactEventKind::Began
+                || event.impulse < MIN_IMPACT_IMPULSE
+                || !bullets.contains(event.entity)
+                || event.other.map_or(false, |other| bullets.contains(other))
+            {
+                continue;
+            }
+
+            let mut transform = Transform::default();
+            transform.set_translation_xyz(event.point.x, event.point.y, event.point.z);
+
+            // The normal points from the bullet to the other body.
+            lazy.create_entity(&entities)
+                .with(transform)
+                .with(ParticleEmitter::impact(-event.normal))
+                .build();
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.contact_reader = Some(
+            res.fetch_mut::<EventChannel<ContactEvent>>()
+                .register_reader(),
+        );
+    }
+}
+
+fn spawn_particle(emitter: &ParticleEmitter, rng: &mut ThreadRng) -> Particle {
+    let random_direction = Vector3::new(
+        rng.gen_range(-1.0, 1.0),
+        rng.gen_range(-1.0, 1.0),
+        rng.gen_range(-1.0, 1.0),
+    );
+    let direction = emitter.direction.try_normalize(std::f32::EPSILON).unwrap_or_else(Vector3::y);
+    let direction = (direction * (1.0 - emitter.spread) + random_direction * emitter.spread)
+        .try_normalize(std::f32::EPSILON)
+        .unwrap_or(direction);
+
+    let speed = if emitter.max_speed > emitter.min_speed {
+        rng.gen_range(emitter.min_speed, emitter.max_speed)
+    } else {
+        emitter.min_speed
+    };
+
+    Particle {
+        age: 0.0,
+        lifetime: emitter.lifetime,
+        velocity: direction * speed,
+        gravity: emitter.gravity,
+        start_size: emitter.start_size,
+        end_size: emitter.end_size,
+        start_color: emitter.start_color,
+        end_color: emitter.end_color,
+    }
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+fn color_tint(from: &[f32; 4], to: &[f32; 4], t: f32) -> Tint {
+    Tint(Srgba::new(
+        lerp(from[0], to[0], t),
+        lerp(from[1], to[1], t),
+        lerp(from[2], to[2], t),
+        lerp(from[3], to[3], t),
+    ))
+}
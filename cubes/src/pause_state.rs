@@ -0,0 +1,71 @@
+use crate::{menu::Menu, menu_state::MainMenuState};
+
+use amethyst::{
+    core::Time,
+    input::{is_key_down, VirtualKeyCode},
+    prelude::{GameData, SimpleState, SimpleTrans, StateData, Trans},
+    StateEvent,
+};
+
+const RESUME_ITEM: usize = 0;
+
+/// Pushed over the `CubeGameState`; it stops the game time, so the physics doesn't step.
+pub struct PauseState {
+    menu: Option<Menu>,
+    /// Restored when the game is resumed.
+    time_scale: f32,
+}
+
+impl PauseState {
+    pub fn new() -> Self {
+        PauseState {
+            menu: None,
+            time_scale: 1.0,
+        }
+    }
+}
+
+impl SimpleState for PauseState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        {
+            let mut time = data.world.write_resource::<Time>();
+            self.time_scale = time.time_scale();
+            time.set_time_scale(0.0);
+        }
+
+        self.menu = Some(Menu::new(
+            data.world,
+            "Paused",
+            &["Resume".to_string(), "Main menu".to_string()],
+        ));
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        data.world.write_resource::<Time>().set_time_scale(self.time_scale);
+
+        if let Some(mut menu) = self.menu.take() {
+            menu.clear(data.world);
+        }
+    }
+
+    fn handle_event(
+        &mut self,
+        _data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        if let StateEvent::Window(event) = &event {
+            if is_key_down(event, VirtualKeyCode::Escape) {
+                return Trans::Pop;
+            }
+        }
+
+        match self.menu.as_ref().and_then(|menu| menu.clicked_item(&event)) {
+            Some(RESUME_ITEM) => Trans::Pop,
+            Some(_) => Trans::Sequence(vec![
+                Trans::Pop,
+                Trans::Switch(Box::new(MainMenuState::new())),
+            ]),
+            None => Trans::None,
+        }
+    }
+}
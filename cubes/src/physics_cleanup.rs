@@ -0,0 +1,285 @@
+use crate::physics_tag_index::PhysicsTagIndex;
+
+use amethyst::{
+    ecs::{prelude::World, Entities, Entity, Join, System, Write, WriteExpect, WriteStorage},
+    phythyst::{objects::*, servers::*},
+};
+
+/// Entities to delete at the end of the frame, together with their bodies and areas.
+///
+/// The tags are plain handles, so deleting an entity leaves its body in the
+/// physics world; the gameplay systems queue the entities here instead, and the
+/// `PhysicsCleanupSystem` deletes them.
+#[derive(Default)]
+pub struct PendingDeletions {
+    entities: Vec<Entity>,
+}
+
+impl PendingDeletions {
+    /// Queues the entity; queuing it again does nothing.
+    pub fn delete(&mut self, entity: Entity) {
+        if !self.contains(entity) {
+            self.entities.push(entity);
+        }
+    }
+
+    /// Returns true when the entity is going to be deleted at the end of the frame.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entities.contains(&entity)
+    }
+
+    pub fn clear(&mut self) {
+        self.entities.clear();
+    }
+}
+
+/// Deletes the `PendingDeletions`, after destroying their bodies and areas.
+///
+/// It must run after every system that queues deletions.
+#[derive(Default)]
+pub struct PhysicsCleanupSystem;
+
+impl<'s> System<'s> for PhysicsCleanupSystem {
+    type SystemData = (
+        Entities<'s>,
+        Write<'s, PendingDeletions>,
+        Write<'s, PhysicsTagIndex>,
+        WriteExpect<'s, RBodyPhysicsServer<f32>>,
+        WriteExpect<'s, AreaPhysicsServer>,
+        WriteStorage<'s, PhysicsBodyTag>,
+        WriteStorage<'s, PhysicsAreaTag>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, mut pending, mut tag_index, mut body_server, mut area_server, mut body_tags, mut area_tags): Self::SystemData,
+    ) {
+        for entity in pending.entities.drain(..) {
+            if !entities.is_alive(entity) {
+                continue;
+            }
+            release(
+                entity,
+                &mut tag_index,
+                &mut body_server,
+                &mut area_server,
+                &mut body_tags,
+                &mut area_tags,
+            );
+            entities.delete(entity).unwrap();
+        }
+    }
+}
+
+/// Destroys, in the physics servers, the bodies and areas owned by the entities,
+/// and removes their tags.
+///
+/// Call it before `World::delete_all`.
+pub fn destroy_bodies_and_areas(world: &mut World) {
+    let mut entities: Vec<Entity> = (&world.entities(), &world.read_storage::<PhysicsBodyTag>())
+        .join()
+        .map(|(e, _)| e)
+        .collect();
+    entities.extend(
+        (&world.entities(), &world.read_storage::<PhysicsAreaTag>())
+            .join()
+            .map(|(e, _)| e),
+    );
+    // An entity can own both a body and an area.
+    entities.sort();
+    entities.dedup();
+    destroy_physics_of(world, &entities);
+
+    // Their bodies are gone, and the entities are about to be deleted.
+    world.write_resource::<PendingDeletions>().clear();
+}
+
+/// Like `destroy_bodies_and_areas`, but only for the given entities; call it before
+/// deleting them.
+pub fn destroy_physics_of(world: &mut World, entities: &[Entity]) {
+    let mut tag_index = world.write_resource::<PhysicsTagIndex>();
+    let mut body_server = world.write_resource::<RBodyPhysicsServer<f32>>();
+    let mut area_server = world.write_resource::<AreaPhysicsServer>();
+    let mut body_tags = world.write_storage::<PhysicsBodyTag>();
+    let mut area_tags = world.write_storage::<PhysicsAreaTag>();
+
+    for entity in entities {
+        release(
+            *entity,
+            &mut tag_index,
+            &mut body_server,
+            &mut area_server,
+            &mut body_tags,
+            &mut area_tags,
+        );
+    }
+}
+
+fn release(
+    entity: Entity,
+    tag_index: &mut PhysicsTagIndex,
+    body_server: &mut RBodyPhysicsServer<f32>,
+    area_server: &mut AreaPhysicsServer,
+    body_tags: &mut WriteStorage<PhysicsBodyTag>,
+    area_tags: &mut WriteStorage<PhysicsAreaTag>,
+) {
+    if let Some(body) = body_tags.remove(entity) {
+        tag_index.remove_body(body);
+        body_server.drop_body(body);
+    }
+    if let Some(area) = area_tags.remove(entity) {
+        tag_index.remove_area(area);
+        area_server.drop_area(area);
+    }
+}
+
+/// Destroys the shapes in the shape server; the bodies and areas that use them
+/// must be destroyed first.
+pub fn destroy_shapes(world: &mut World, shapes: &[PhysicsShapeTag]) {
+    let mut shape_server = world.write_resource::<ShapePhysicsServer<f32>>();
+    for shape in shapes {
+        shape_server.drop_shape(*shape);
+    }
+}
+
+/// Returns the number of bodies that still exist in the physics world.
+///
+/// It's `0` once a state cleaned up everything it created.
+pub fn remaining_bodies(world: &World) -> usize {
+    let world_server = world.read_resource::<WorldPhysicsServer<f32>>();
+    let physics_world = world.read_resource::<PhysicsWorldTag>();
+    world_server.body_count(*physics_world)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game_state::{create_area, create_rigid_body, InitialVelocity},
+        physics_material::PhysicsMaterial,
+    };
+
+    use amethyst::{
+        amethyst_nphysics,
+        core::{math::Vector3, Transform},
+        phythyst::PhysicsBundle,
+        prelude::{Application, Builder, GameData, GameDataBuilder, SimpleState, SimpleTrans, StateData, Trans},
+    };
+
+    /// Bodies still in the physics world: after the queued deletions, and at the end.
+    #[derive(Default)]
+    struct CleanupReport {
+        after_pending: usize,
+        at_the_end: usize,
+        /// Tags still in the `PhysicsTagIndex` at the end.
+        indexed_at_the_end: usize,
+    }
+
+    /// Spawns some bodies and an area, deletes half of them through the
+    /// `PendingDeletions` and then cleans up the rest like a state does on stop.
+    #[derive(Default)]
+    struct CleanupState {
+        frame: u32,
+        spawned: Vec<Entity>,
+        bodies: Vec<PhysicsBodyTag>,
+        area: Option<PhysicsAreaTag>,
+        shape: Option<PhysicsShapeTag>,
+    }
+
+    impl SimpleState for CleanupState {
+        fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+            let shape = data
+                .world
+                .write_resource::<ShapePhysicsServer<f32>>()
+                .create_shape(&ShapeDesc::Sphere { radius: 0.5 });
+            self.shape = Some(shape);
+
+            for i in 0..6 {
+                let mut transf = Transform::default();
+                transf.set_translation_xyz(i as f32 * 2.0, 0.0, 0.0);
+                let rb = create_rigid_body(
+                    data.world,
+                    &transf,
+                    shape,
+                    BodyMode::Dynamic,
+                    &PhysicsMaterial::default(),
+                    &InitialVelocity {
+                        linear: Vector3::new(0.0, -1.0, 0.0),
+                        ..Default::default()
+                    },
+                );
+                let entity = data.world.create_entity().with(transf).with(rb).build();
+                data.world.write_resource::<PhysicsTagIndex>().insert_body(rb, entity);
+                self.spawned.push(entity);
+                self.bodies.push(rb);
+            }
+
+            let area = create_area(data.world, &Transform::default(), shape);
+            let entity = data.world.create_entity().with(Transform::default()).with(area).build();
+            data.world.write_resource::<PhysicsTagIndex>().insert_area(area, entity);
+            self.area = Some(area);
+        }
+
+        fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+            self.frame += 1;
+            match self.frame {
+                1 => {
+                    let mut pending = data.world.write_resource::<PendingDeletions>();
+                    for entity in self.spawned.iter().step_by(2) {
+                        pending.delete(*entity);
+                        // Queued twice, like a bullet that hits two targets in a frame.
+                        pending.delete(*entity);
+                    }
+                    Trans::None
+                }
+                2 => {
+                    data.world.write_resource::<CleanupReport>().after_pending =
+                        remaining_bodies(data.world);
+
+                    destroy_bodies_and_areas(data.world);
+                    destroy_shapes(data.world, &[self.shape.take().unwrap()]);
+                    data.world.delete_all();
+
+                    let indexed = {
+                        let tag_index = data.world.read_resource::<PhysicsTagIndex>();
+                        self.bodies
+                            .iter()
+                            .filter(|body| tag_index.body_entity(**body).is_some())
+                            .count()
+                            + self
+                                .area
+                                .iter()
+                                .filter(|area| tag_index.area_entity(**area).is_some())
+                                .count()
+                    };
+
+                    let mut report = data.world.write_resource::<CleanupReport>();
+                    report.at_the_end = remaining_bodies(data.world);
+                    report.indexed_at_the_end = indexed;
+                    Trans::Quit
+                }
+                _ => Trans::None,
+            }
+        }
+    }
+
+    #[test]
+    fn no_body_survives_the_cleanup() -> amethyst::Result<()> {
+        let game_data = GameDataBuilder::default()
+            .with_bundle(PhysicsBundle::new())?
+            .with_barrier()
+            .with(PhysicsCleanupSystem, "physics_cleanup_system", &[]);
+
+        let mut game = Application::build("./", CleanupState::default())?
+            .with_physics(amethyst_nphysics::create_physics::<f32>())
+            .with_resource(CleanupReport::default())
+            .build(game_data)?;
+        game.run();
+
+        let report = game.world().read_resource::<CleanupReport>();
+        assert_eq!(report.after_pending, 3);
+        assert_eq!(report.at_the_end, 0);
+        assert_eq!(report.indexed_at_the_end, 0);
+        Ok(())
+    }
+}
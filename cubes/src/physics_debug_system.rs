@@ -0,0 +1,176 @@
+use crate::{components::*, transform_utils::*};
+
+use amethyst::{
+    core::{
+        math::{Isometry3, Point3, Translation3, Vector3},
+        Transform,
+    },
+    ecs::{Join, Read, ReadExpect, ReadStorage, System, Write},
+    input::{InputHandler, StringBindings},
+    phythyst::{objects::*, servers::*},
+    renderer::{debug_drawing::DebugLines, palette::Srgba},
+};
+
+/// Controls what the `PhysicsDebugSystem` draws.
+/// Press the `toggle_physics_debug` action to switch it on and off at runtime.
+pub struct PhysicsDebug {
+    pub enabled: bool,
+    pub draw_shapes: bool,
+    pub draw_velocities: bool,
+    pub draw_contacts: bool,
+}
+
+impl Default for PhysicsDebug {
+    fn default() -> Self {
+        PhysicsDebug {
+            enabled: false,
+            draw_shapes: true,
+            draw_velocities: true,
+            draw_contacts: true,
+        }
+    }
+}
+
+pub struct PhysicsDebugSystem {
+    toggle_was_down: bool,
+}
+
+impl PhysicsDebugSystem {
+    pub fn new() -> Self {
+        PhysicsDebugSystem {
+            toggle_was_down: false,
+        }
+    }
+}
+
+impl<'s> System<'s> for PhysicsDebugSystem {
+    type SystemData = (
+        Read<'s, InputHandler<StringBindings>>,
+        Write<'s, PhysicsDebug>,
+        Write<'s, DebugLines>,
+        ReadExpect<'s, RBodyPhysicsServer<f32>>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, ColliderShape>,
+        ReadStorage<'s, PhysicsBodyTag>,
+        ReadStorage<'s, PhysicsAreaTag>,
+    );
+
+    fn run(
+        &mut self,
+        (input, mut debug, mut lines, body_server, transforms, shapes, bodies, areas): Self::SystemData,
+    ) {
+        let toggle_is_down = input.action_is_down("toggle_physics_debug").unwrap_or(false);
+        if toggle_is_down && !self.toggle_was_down {
+            debug.enabled = !debug.enabled;
+        }
+        self.toggle_was_down = toggle_is_down;
+
+        if !debug.enabled {
+            return;
+        }
+
+        if debug.draw_shapes {
+            for (transform, shape, _) in (&transforms, &shapes, &bodies).join() {
+                draw_shape(&mut lines, transform, &shape.desc, Srgba::new(0.0, 1.0, 1.0, 1.0));
+            }
+
+            for (transform, shape, _) in (&transforms, &shapes, &areas).join() {
+                draw_shape(&mut lines, transform, &shape.desc, Srgba::new(1.0, 1.0, 0.0, 1.0));
+            }
+        }
+
+        if debug.draw_velocities {
+            for (transform, body) in (&transforms, &bodies).join() {
+                // Static and kinematic bodies are moved by the game, not by the physics.
+                if body_server.mode(*body) != BodyMode::Dynamic {
+                    continue;
+                }
+                let velocity = body_server.linear_velocity(*body);
+                if velocity.norm_squared() > 0.0 {
+                    lines.draw_direction(
+                        position_f32(transform),
+                        velocity * 0.1,
+                        Srgba::new(1.0, 0.0, 1.0, 1.0),
+                    );
+                }
+            }
+        }
+
+        if debug.draw_contacts {
+            for body in (&bodies).join() {
+                for contact in body_server.contact_events(*body) {
+                    let point = Point3::from(contact.contact_point);
+                    lines.draw_sphere(point, 0.1, 4, 4, Srgba::new(1.0, 0.0, 0.0, 1.0));
+                    lines.draw_direction(point, contact.normal, Srgba::new(1.0, 0.5, 0.0, 1.0));
+                }
+            }
+        }
+    }
+}
+
+fn draw_shape(lines: &mut DebugLines, transform: &Transform, desc: &ShapeDesc<f32>, color: Srgba) {
+    draw_shape_at(
+        lines,
+        &Isometry3::from_parts(Translation3::from(translation_f32(transform)), rotation_f32(transform)),
+        desc,
+        color,
+    );
+}
+
+fn draw_shape_at(lines: &mut DebugLines, isometry: &Isometry3<f32>, desc: &ShapeDesc<f32>, color: Srgba) {
+    let position = Point3::from(isometry.translation.vector);
+    let rotation = isometry.rotation;
+
+    match desc {
+        ShapeDesc::Sphere { radius } => {
+            lines.draw_rotated_sphere(position, *radius, 16, 16, rotation, color);
+        }
+        ShapeDesc::Cube { half_extents } => {
+            lines.draw_rotated_box(
+                position - half_extents,
+                position + half_extents,
+                rotation,
+                color,
+            );
+        }
+        ShapeDesc::Cylinder {
+            half_height,
+            radius,
+        } => {
+            // Aligned along the Z axis, like the `Shape::Cylinder` mesh.
+            let axis = rotation * Vector3::z() * *half_height;
+            lines.draw_cylinder(position + axis, position - axis, *radius, 16, color);
+        }
+        ShapeDesc::Capsule {
+            half_height,
+            radius,
+        } => {
+            let axis = rotation * Vector3::z() * *half_height;
+            lines.draw_capsule(position + axis, position - axis, *radius, 16, color);
+        }
+        ShapeDesc::Convex { points } => {
+            // The hull is not computed here, so the points are drawn as small crosses.
+            for p in points {
+                let p = isometry * Point3::from(*p);
+                lines.draw_direction(p, Vector3::x() * 0.1, color);
+                lines.draw_direction(p, Vector3::y() * 0.1, color);
+                lines.draw_direction(p, Vector3::z() * 0.1, color);
+            }
+        }
+        ShapeDesc::TriMesh { points, indices } => {
+            for triangle in indices {
+                let a = isometry * Point3::from(points[triangle[0]]);
+                let b = isometry * Point3::from(points[triangle[1]]);
+                let c = isometry * Point3::from(points[triangle[2]]);
+                lines.draw_line(a, b, color);
+                lines.draw_line(b, c, color);
+                lines.draw_line(c, a, color);
+            }
+        }
+        ShapeDesc::Compound { shapes } => {
+            for (offset, shape) in shapes {
+                draw_shape_at(lines, &(isometry * offset), shape, color);
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+/// Physical properties of a rigid body.
+///
+/// It can be set from code, when spawning an entity, or from the level data.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhysicsMaterial {
+    pub mass: f32,
+    pub friction: f32,
+    pub restitution: f32,
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    pub gravity_scale: f32,
+    /// Enables the continuous collision detection; use it for small and fast bodies.
+    pub ccd: bool,
+}
+
+impl Default for PhysicsMaterial {
+    fn default() -> Self {
+        PhysicsMaterial {
+            mass: 1.0,
+            friction: 0.5,
+            restitution: 0.0,
+            linear_damping: 0.0,
+            angular_damping: 0.0,
+            gravity_scale: 1.0,
+            ccd: false,
+        }
+    }
+}
+
+impl PhysicsMaterial {
+    pub fn bouncy() -> Self {
+        PhysicsMaterial {
+            restitution: 0.8,
+            friction: 0.2,
+            ..Default::default()
+        }
+    }
+
+    pub fn slippery() -> Self {
+        PhysicsMaterial {
+            friction: 0.02,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{components::*, physics_tag_index::PhysicsTagIndex, transform_utils::*};
+
+use amethyst::{
+    core::{
+        math::{Point3, Vector3},
+        Transform,
+    },
+    ecs::{Entities, Join, Read, ReadExpect, ReadStorage, System},
+    phythyst::{objects::*, servers::*, PhysicsTime},
+};
+
+use std::collections::{HashMap, HashSet};
+
+/// Applies the `PhysicsModifier` of each area to the bodies that are inside it.
+///
+/// The velocity is changed directly, so the effect doesn't depend on the mass of the body.
+/// Only the dynamic bodies are affected.
+///
+/// #IMPORTANT
+/// It runs at each physics step, see `PhysicsBundle::with_in_physics`.
+#[derive(Default)]
+pub struct PhysicsModifierSystem {
+    bodies_inside: HashMap<PhysicsAreaTag, HashSet<PhysicsBodyTag>>,
+}
+
+impl<'s> System<'s> for PhysicsModifierSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadExpect<'s, PhysicsTime>,
+        ReadExpect<'s, PhysicsWorldTag>,
+        ReadExpect<'s, WorldPhysicsServer<f32>>,
+        ReadExpect<'s, RBodyPhysicsServer<f32>>,
+        ReadExpect<'s, AreaPhysicsServer>,
+        Read<'s, PhysicsTagIndex>,
+        ReadStorage<'s, PhysicsAreaTag>,
+        ReadStorage<'s, PhysicsModifier>,
+        ReadStorage<'s, Transform>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, physics_time, physics_world, world_server, body_server, area_server, tag_index, areas, modifiers, transforms): Self::SystemData,
+    ) {
+        let delta = physics_time.delta_seconds();
+        let world_gravity = world_server.gravity(*physics_world);
+
+        // Forget the areas that are gone
+        let alive_areas: HashSet<PhysicsAreaTag> = (&areas, &modifiers).join().map(|(a, _)| *a).collect();
+        self.bodies_inside.retain(|area, _| alive_areas.contains(area));
+
+        for (_, area, modifier) in (&*entities, &areas, &modifiers).join() {
+            let inside = self.bodies_inside.entry(*area).or_insert_with(HashSet::new);
+
+            for e in area_server.0.overlap_events(*area) {
+                match e {
+                    OverlapEvent::Enter(body) => {
+                        inside.insert(body);
+                    }
+                    OverlapEvent::Exit(body) => {
+                        inside.remove(&body);
+                    }
+                }
+            }
+
+            // Drop the bodies deleted while inside the area
+            inside.retain(|body| {
+                tag_index
+                    .body_entity(*body)
+                    .map_or(false, |entity| entities.is_alive(entity))
+            });
+
+            for body in inside.iter() {
+                if body_server.mode(*body) != BodyMode::Dynamic {
+                    continue;
+                }
+
+                let velocity = body_server.linear_velocity(*body);
+                let mut acceleration = Vector3::from(modifier.wind);
+
+                // The world gravity is scaled per body, so the override is scaled as well.
+                if let Some(gravity) = modifier.gravity {
+                    acceleration +=
+                        (Vector3::from(gravity) - world_gravity) * body_server.gravity_scale(*body);
+                }
+
+                acceleration -= velocity * modifier.drag;
+
+                if let Some(attractor) = &modifier.attractor {
+                    let position = tag_index
+                        .body_entity(*body)
+                        .and_then(|entity| transforms.get(entity))
+                        .map(position_f32);
+                    if let Some(position) = position {
+                        let offset = Point3::from(Vector3::from(attractor.point)) - position;
+                        let distance_squared = offset.norm_squared();
+                        if distance_squared > 0.0 {
+                            acceleration += offset.normalize() * attractor.strength
+                                / distance_squared.max(1.0);
+                        }
+                    }
+                }
+
+                body_server.set_linear_velocity(*body, &(velocity + acceleration * delta));
+            }
+        }
+    }
+}
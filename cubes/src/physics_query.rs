@@ -0,0 +1,122 @@
+use crate::physics_tag_index::PhysicsTagIndex;
+
+use amethyst::{
+    core::math::{Isometry3, Point3, Vector3},
+    ecs::{Entities, Entity, Read, ReadExpect, SystemData},
+    phythyst::{objects::*, servers::*},
+};
+
+/// The result of a ray or shape cast.
+#[derive(Clone, Debug)]
+pub struct QueryHit {
+    pub entity: Entity,
+    pub point: Point3<f32>,
+    pub normal: Vector3<f32>,
+    pub distance: f32,
+}
+
+/// Wraps the physics servers to run spatial queries, which return entities instead of tags.
+///
+/// Take it in the `SystemData` of a system, or fetch it with `world.system_data::<PhysicsQuery>()`.
+#[derive(SystemData)]
+pub struct PhysicsQuery<'s> {
+    entities: Entities<'s>,
+    world_server: ReadExpect<'s, WorldPhysicsServer<f32>>,
+    physics_world: ReadExpect<'s, PhysicsWorldTag>,
+    tag_index: Read<'s, PhysicsTagIndex>,
+}
+
+impl<'s> PhysicsQuery<'s> {
+    /// Returns the closest body hit by the ray.
+    /// `direction` doesn't need to be normalized.
+    pub fn raycast(
+        &self,
+        origin: &Point3<f32>,
+        direction: &Vector3<f32>,
+        max_distance: f32,
+    ) -> Option<QueryHit> {
+        let direction = direction.normalize();
+        let hit = self
+            .world_server
+            .ray_cast(*self.physics_world, origin, &direction, max_distance)?;
+
+        Some(QueryHit {
+            entity: self.body_entity(hit.body)?,
+            point: origin + direction * hit.distance,
+            normal: hit.normal,
+            distance: hit.distance,
+        })
+    }
+
+    /// Moves `shape` from `start` along `direction` and returns the first body it touches.
+    pub fn shape_cast(
+        &self,
+        shape: PhysicsShapeTag,
+        start: &Isometry3<f32>,
+        direction: &Vector3<f32>,
+        max_distance: f32,
+    ) -> Option<QueryHit> {
+        let direction = direction.normalize();
+        let hit = self.world_server.shape_cast(
+            *self.physics_world,
+            shape,
+            start,
+            &direction,
+            max_distance,
+        )?;
+
+        Some(QueryHit {
+            entity: self.body_entity(hit.body)?,
+            point: Point3::from(start.translation.vector) + direction * hit.distance,
+            normal: hit.normal,
+            distance: hit.distance,
+        })
+    }
+
+    /// Returns the entities of the bodies that contain the point.
+    pub fn overlap_point(&self, point: &Point3<f32>) -> Vec<Entity> {
+        self.world_server
+            .overlap_point(*self.physics_world, point)
+            .into_iter()
+            .filter_map(|body| self.body_entity(body))
+            .collect()
+    }
+
+    /// Returns the entities of the bodies that intersect the sphere.
+    pub fn overlap_sphere(&self, center: &Point3<f32>, radius: f32) -> Vec<Entity> {
+        self.world_server
+            .overlap_sphere(*self.physics_world, center, radius)
+            .into_iter()
+            .filter_map(|body| self.body_entity(body))
+            .collect()
+    }
+
+    /// Returns true when nothing stands between the two points.
+    /// Pass the entities of the viewer and of the target in `ignore`.
+    pub fn has_line_of_sight(&self, from: &Point3<f32>, to: &Point3<f32>, ignore: &[Entity]) -> bool {
+        let direction = to - from;
+        let distance = direction.norm();
+        match self.raycast(from, &direction, distance) {
+            Some(hit) => ignore.contains(&hit.entity),
+            None => true,
+        }
+    }
+
+    pub fn entities(&self) -> &Entities<'s> {
+        &self.entities
+    }
+
+    /// Returns the entity of the body, if it's still alive.
+    pub fn body_entity(&self, body: PhysicsBodyTag) -> Option<Entity> {
+        self.tag_index
+            .body_entity(body)
+            .filter(|e| self.entities.is_alive(*e))
+    }
+
+    /// Returns the entity of the area, if it's still alive.
+    pub fn area_entity(&self, area: PhysicsAreaTag) -> Option<Entity> {
+        self.tag_index
+            .area_entity(area)
+            .filter(|e| self.entities.is_alive(*e))
+    }
+}
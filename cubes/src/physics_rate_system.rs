@@ -0,0 +1,101 @@
+use crate::physics_stats::PhysicsStats;
+
+use amethyst::{
+    core::Time,
+    ecs::{Read, ReadExpect, System, Write, WriteExpect},
+    phythyst::PhysicsTime,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Configures the physics rate, loaded from `configs/physics_conf.ron`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhysicsRateConfig {
+    pub frames_per_second: u32,
+    /// When a frame needs more steps than this, the physics drops the exceeding time,
+    /// so it runs slower than the game.
+    pub max_sub_steps: u32,
+    /// Lets the rate move between `min_frames_per_second` and `frames_per_second`
+    /// to keep the physics within `step_budget_ms` each frame.
+    pub adaptive: bool,
+    pub min_frames_per_second: u32,
+    pub step_budget_ms: f32,
+}
+
+impl Default for PhysicsRateConfig {
+    fn default() -> Self {
+        PhysicsRateConfig {
+            frames_per_second: 240,
+            max_sub_steps: 8,
+            adaptive: false,
+            min_frames_per_second: 60,
+            step_budget_ms: 4.0,
+        }
+    }
+}
+
+/// The values chosen by the `PhysicsRateSystem`, reported in the stats.
+pub struct PhysicsRate {
+    pub frames_per_second: u32,
+}
+
+impl Default for PhysicsRate {
+    fn default() -> Self {
+        PhysicsRate {
+            frames_per_second: PhysicsRateConfig::default().frames_per_second,
+        }
+    }
+}
+
+impl PhysicsRateConfig {
+    /// The `PhysicsTime` for the given rate; the stepper never executes more than
+    /// `max_sub_steps` in a frame.
+    pub fn physics_time(&self, frames_per_second: u32) -> PhysicsTime {
+        PhysicsTime::default()
+            .set_frames_per_second(frames_per_second)
+            .set_max_sub_steps(self.max_sub_steps)
+    }
+}
+
+/// Rate change applied at once when the rate is adaptive.
+const ADAPTIVE_RATE_STEP: u32 = 10;
+
+/// Adapts the physics rate to the scene load, when `PhysicsRateConfig::adaptive` is set.
+///
+/// It must run after the `PhysicsStatsEndSystem`.
+#[derive(Default)]
+pub struct PhysicsRateSystem;
+
+impl<'s> System<'s> for PhysicsRateSystem {
+    type SystemData = (
+        ReadExpect<'s, PhysicsRateConfig>,
+        Read<'s, PhysicsStats>,
+        Write<'s, PhysicsRate>,
+        ReadExpect<'s, Time>,
+        WriteExpect<'s, PhysicsTime>,
+    );
+
+    fn run(&mut self, (config, stats, mut rate, time, mut physics_time): Self::SystemData) {
+        // The game is paused, see `PauseState`.
+        if time.time_scale() == 0.0 || !config.adaptive {
+            return;
+        }
+
+        let frame = &stats.last_frame;
+        let fps = if frame.step_time_ms > config.step_budget_ms {
+            rate.frames_per_second
+                .saturating_sub(ADAPTIVE_RATE_STEP)
+                .max(config.min_frames_per_second)
+        } else if frame.step_time_ms < config.step_budget_ms * 0.5 {
+            (rate.frames_per_second + ADAPTIVE_RATE_STEP).min(config.frames_per_second)
+        } else {
+            rate.frames_per_second
+        };
+
+        if fps != rate.frames_per_second {
+            rate.frames_per_second = fps;
+            *physics_time = config.physics_time(fps);
+        }
+    }
+}
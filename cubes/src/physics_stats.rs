@@ -0,0 +1,284 @@
+use crate::physics_rate_system::PhysicsRateConfig;
+
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    core::Time,
+    ecs::{
+        prelude::World, Entity, Join, Read, ReadExpect, ReadStorage, System, Write, WriteStorage,
+    },
+    input::{InputHandler, StringBindings},
+    phythyst::{objects::*, servers::*, PhysicsTime},
+    prelude::Builder,
+    ui::{get_default_font, Anchor, FontAsset, UiText, UiTransform},
+};
+
+use std::{
+    collections::VecDeque,
+    fs,
+    io::{self, Write as IoWrite},
+    path::Path,
+    time::Instant,
+};
+
+/// Number of frames kept in the history, exported to CSV.
+const HISTORY_LENGTH: usize = 10_000;
+
+/// The physics statistics of one frame.
+#[derive(Clone, Debug, Default)]
+pub struct PhysicsFrameStats {
+    /// Seconds since the start of the game.
+    pub time: f64,
+    /// Milliseconds spent by the physics during this frame.
+    pub step_time_ms: f32,
+    /// Number of physics steps executed during this frame.
+    pub steps: u32,
+    /// Number of steps executed each second at the configured rate.
+    pub target_frames_per_second: f32,
+    pub max_sub_steps: u32,
+    pub active_bodies: usize,
+    pub sleeping_bodies: usize,
+    /// The contacts and overlaps are counted only while the overlay is visible,
+    /// since it means going through the events of each body and area.
+    pub contacts: usize,
+    pub overlaps: usize,
+}
+
+/// Collects the `PhysicsFrameStats`, see `PhysicsStatsBeginSystem` and `PhysicsStatsEndSystem`.
+pub struct PhysicsStats {
+    pub last_frame: PhysicsFrameStats,
+    pub history: VecDeque<PhysicsFrameStats>,
+    pub overlay_visible: bool,
+    step_start: Option<Instant>,
+    /// Counted by the `PhysicsStepCounterSystem`.
+    steps: u32,
+}
+
+impl Default for PhysicsStats {
+    fn default() -> Self {
+        PhysicsStats {
+            last_frame: PhysicsFrameStats::default(),
+            history: VecDeque::with_capacity(HISTORY_LENGTH),
+            overlay_visible: false,
+            step_start: None,
+            steps: 0,
+        }
+    }
+}
+
+impl PhysicsStats {
+    pub fn export_csv<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        if let Some(dir) = path.as_ref().parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        writeln!(
+            file,
+            "time,step_time_ms,steps,target_frames_per_second,max_sub_steps,active_bodies,sleeping_bodies,contacts,overlaps"
+        )?;
+        for s in self.history.iter() {
+            writeln!(
+                file,
+                "{},{},{},{},{},{},{},{},{}",
+                s.time,
+                s.step_time_ms,
+                s.steps,
+                s.target_frames_per_second,
+                s.max_sub_steps,
+                s.active_bodies,
+                s.sleeping_bodies,
+                s.contacts,
+                s.overlaps
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Marks the start of the physics stepping; it must run right before the `PhysicsBundle`.
+#[derive(Default)]
+pub struct PhysicsStatsBeginSystem;
+
+impl<'s> System<'s> for PhysicsStatsBeginSystem {
+    type SystemData = Write<'s, PhysicsStats>;
+
+    fn run(&mut self, mut stats: Self::SystemData) {
+        stats.step_start = Some(Instant::now());
+        stats.steps = 0;
+    }
+}
+
+/// Counts the physics steps executed during the frame.
+///
+/// #IMPORTANT
+/// It runs at each physics step, see `PhysicsBundle::with_in_physics`.
+#[derive(Default)]
+pub struct PhysicsStepCounterSystem;
+
+impl<'s> System<'s> for PhysicsStepCounterSystem {
+    type SystemData = Write<'s, PhysicsStats>;
+
+    fn run(&mut self, mut stats: Self::SystemData) {
+        stats.steps += 1;
+    }
+}
+
+/// Measures the physics stepping and counts the bodies, contacts and overlaps.
+/// It must run right after the `PhysicsBundle`.
+#[derive(Default)]
+pub struct PhysicsStatsEndSystem;
+
+impl<'s> System<'s> for PhysicsStatsEndSystem {
+    type SystemData = (
+        Write<'s, PhysicsStats>,
+        ReadExpect<'s, Time>,
+        ReadExpect<'s, PhysicsTime>,
+        ReadExpect<'s, PhysicsRateConfig>,
+        ReadExpect<'s, RBodyPhysicsServer<f32>>,
+        ReadExpect<'s, AreaPhysicsServer>,
+        ReadStorage<'s, PhysicsBodyTag>,
+        ReadStorage<'s, PhysicsAreaTag>,
+    );
+
+    fn run(
+        &mut self,
+        (mut stats, time, physics_time, rate_config, body_server, area_server, bodies, areas): Self::SystemData,
+    ) {
+        let step_time_ms = stats
+            .step_start
+            .take()
+            .map_or(0.0, |start| start.elapsed().as_micros() as f32 / 1000.0);
+
+        let mut frame = PhysicsFrameStats {
+            time: time.absolute_time_seconds(),
+            step_time_ms,
+            steps: stats.steps,
+            target_frames_per_second: 1.0 / physics_time.delta_seconds(),
+            max_sub_steps: rate_config.max_sub_steps,
+            ..Default::default()
+        };
+
+        for body in (&bodies).join() {
+            if body_server.is_sleeping(*body) {
+                frame.sleeping_bodies += 1;
+            } else {
+                frame.active_bodies += 1;
+            }
+        }
+
+        if stats.overlay_visible {
+            for body in (&bodies).join() {
+                frame.contacts += body_server.contact_events(*body).len();
+            }
+            // Each contact is reported by both bodies.
+            frame.contacts /= 2;
+
+            for area in (&areas).join() {
+                frame.overlaps += area_server.0.overlap_events(*area).len();
+            }
+        }
+
+        if stats.history.len() >= HISTORY_LENGTH {
+            stats.history.pop_front();
+        }
+        stats.history.push_back(frame.clone());
+        stats.last_frame = frame;
+    }
+}
+
+/// Keeps the text entity that shows the physics statistics.
+#[derive(Default)]
+pub struct PhysicsStatsOverlay {
+    text: Option<Entity>,
+}
+
+/// Creates the text entity used by the `PhysicsStatsOverlaySystem`.
+pub fn create_overlay(world: &mut World) {
+    let font = {
+        let loader = world.read_resource::<Loader>();
+        let font_storage = world.read_resource::<AssetStorage<FontAsset>>();
+        get_default_font(&loader, &font_storage)
+    };
+
+    let text = world
+        .create_entity()
+        .with(UiTransform::new(
+            "physics_stats".to_string(),
+            Anchor::TopLeft,
+            Anchor::TopLeft,
+            10.0,
+            -10.0,
+            1.0,
+            400.0,
+            140.0,
+        ))
+        .with(UiText::new(font, String::new(), [1.0, 1.0, 1.0, 1.0], 14.0))
+        .build();
+
+    world.add_resource(PhysicsStatsOverlay { text: Some(text) });
+}
+
+/// Shows the `PhysicsStats` on screen, and exports them to CSV on request.
+pub struct PhysicsStatsOverlaySystem {
+    toggle_was_down: bool,
+    export_was_down: bool,
+}
+
+impl PhysicsStatsOverlaySystem {
+    pub fn new() -> Self {
+        PhysicsStatsOverlaySystem {
+            toggle_was_down: false,
+            export_was_down: false,
+        }
+    }
+}
+
+impl<'s> System<'s> for PhysicsStatsOverlaySystem {
+    type SystemData = (
+        Read<'s, InputHandler<StringBindings>>,
+        Write<'s, PhysicsStats>,
+        Read<'s, PhysicsStatsOverlay>,
+        WriteStorage<'s, UiText>,
+    );
+
+    fn run(&mut self, (input, mut stats, overlay, mut texts): Self::SystemData) {
+        let toggle_is_down = input.action_is_down("toggle_physics_stats").unwrap_or(false);
+        if toggle_is_down && !self.toggle_was_down {
+            stats.overlay_visible = !stats.overlay_visible;
+        }
+        self.toggle_was_down = toggle_is_down;
+
+        let export_is_down = input.action_is_down("export_physics_stats").unwrap_or(false);
+        if export_is_down && !self.export_was_down {
+            let path = crate::get_dir_path("/stats/physics_stats.csv");
+            match stats.export_csv(&path) {
+                Ok(()) => log::info!("Physics stats exported to {}", path),
+                Err(e) => log::error!("Can't export the physics stats: {}", e),
+            }
+        }
+        self.export_was_down = export_is_down;
+
+        let text = match overlay.text.and_then(|e| texts.get_mut(e)) {
+            Some(text) => text,
+            None => return,
+        };
+
+        if !stats.overlay_visible {
+            text.text.clear();
+            return;
+        }
+
+        let s = &stats.last_frame;
+        text.text = format!(
+            "Physics step: {:.3} ms\nSteps this frame: {} of {} (at {:.0} fps)\nBodies: {} active, {} sleeping\nContacts: {}\nOverlaps: {}",
+            s.step_time_ms,
+            s.steps,
+            s.max_sub_steps,
+            s.target_frames_per_second,
+            s.active_bodies,
+            s.sleeping_bodies,
+            s.contacts,
+            s.overlaps
+        );
+    }
+}
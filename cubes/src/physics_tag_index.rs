@@ -0,0 +1,39 @@
+use amethyst::{ecs::Entity, phythyst::objects::*};
+
+use std::collections::HashMap;
+
+/// Maps the physics tags back to the entities that own them.
+///
+/// The spawn functions register the tags once the entity is built, and
+/// `physics_cleanup` removes them when the body or area is destroyed.
+#[derive(Default)]
+pub struct PhysicsTagIndex {
+    bodies: HashMap<PhysicsBodyTag, Entity>,
+    areas: HashMap<PhysicsAreaTag, Entity>,
+}
+
+impl PhysicsTagIndex {
+    pub fn body_entity(&self, body: PhysicsBodyTag) -> Option<Entity> {
+        self.bodies.get(&body).cloned()
+    }
+
+    pub fn area_entity(&self, area: PhysicsAreaTag) -> Option<Entity> {
+        self.areas.get(&area).cloned()
+    }
+
+    pub fn insert_body(&mut self, body: PhysicsBodyTag, entity: Entity) {
+        self.bodies.insert(body, entity);
+    }
+
+    pub fn insert_area(&mut self, area: PhysicsAreaTag, entity: Entity) {
+        self.areas.insert(area, entity);
+    }
+
+    pub fn remove_body(&mut self, body: PhysicsBodyTag) {
+        self.bodies.remove(&body);
+    }
+
+    pub fn remove_area(&mut self, area: PhysicsAreaTag) {
+        self.areas.remove(&area);
+    }
+}
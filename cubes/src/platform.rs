@@ -0,0 +1,77 @@
+use amethyst::{
+    core::math::Vector3,
+    phythyst::objects::ShapeDesc,
+    renderer::{
+        rendy::mesh::{Normal, Position, Tangent, TexCoord},
+        shape::Shape,
+        types,
+    },
+};
+
+/// Describes the geometry of a platform.
+///
+/// Both the render mesh and the physics shape are generated from this description,
+/// so what is drawn is always what the bodies collide with.
+#[derive(Clone, Debug)]
+pub enum PlatformDesc {
+    Box { half_extents: Vector3<f32> },
+    /// Cylinder aligned along the Z axis, like `Shape::Cylinder`.
+    Cylinder { radius: f32, half_height: f32 },
+    Sphere { radius: f32 },
+    /// Plane lying on the XY axis; its collider is a thin box.
+    Plane { half_width: f32, half_height: f32 },
+}
+
+/// Thickness given to the collider of a `PlatformDesc::Plane`.
+const PLANE_HALF_THICKNESS: f32 = 0.01;
+
+impl PlatformDesc {
+    pub fn mesh_data(&self) -> types::MeshData {
+        let (shape, scale) = match self {
+            PlatformDesc::Box { half_extents } => (
+                Shape::Cube,
+                (half_extents.x, half_extents.y, half_extents.z),
+            ),
+            PlatformDesc::Cylinder {
+                radius,
+                half_height,
+            } => (
+                Shape::Cylinder(128usize, Some(1usize)),
+                (*radius, *radius, *half_height),
+            ),
+            PlatformDesc::Sphere { radius } => {
+                (Shape::Sphere(32, 32), (*radius, *radius, *radius))
+            }
+            PlatformDesc::Plane {
+                half_width,
+                half_height,
+            } => (Shape::Plane(None), (*half_width, *half_height, 1.0)),
+        };
+
+        shape
+            .generate::<(Vec<Position>, Vec<Normal>, Vec<Tangent>, Vec<TexCoord>)>(Some(scale))
+            .into()
+    }
+
+    pub fn shape_desc(&self) -> ShapeDesc<f32> {
+        match self {
+            PlatformDesc::Box { half_extents } => ShapeDesc::Cube {
+                half_extents: *half_extents,
+            },
+            PlatformDesc::Cylinder {
+                radius,
+                half_height,
+            } => ShapeDesc::Cylinder {
+                half_height: *half_height,
+                radius: *radius,
+            },
+            PlatformDesc::Sphere { radius } => ShapeDesc::Sphere { radius: *radius },
+            PlatformDesc::Plane {
+                half_width,
+                half_height,
+            } => ShapeDesc::Cube {
+                half_extents: Vector3::new(*half_width, *half_height, PLANE_HALF_THICKNESS),
+            },
+        }
+    }
+}
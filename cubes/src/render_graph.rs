@@ -14,6 +14,7 @@ use amethyst::{
         },
         types, GraphCreator,
     },
+    ui::DrawUiDesc,
     window::{ScreenDimensions, Window},
 };
 
@@ -86,6 +87,8 @@ impl GraphCreator<types::DefaultBackend> for MyRenderGraphCreator {
             SubpassBuilder::new()
                 .with_group(pass::DrawPbrDesc::default().builder()) // Pass 1
                 .with_group(pass::DrawPbrTransparentDesc::default().builder()) // Pass 2
+                .with_group(pass::DrawDebugLinesDesc::new().builder()) // Physics debug
+                .with_group(DrawUiDesc::new().builder()) // Overlays
                 //.with_group(pass::DrawFlatDesc::default().builder())
                 .with_color(color_image)
                 .with_depth_stencil(depth)
@@ -0,0 +1,106 @@
+use crate::{
+    game_mode::HighScores, game_state::CubeGameState, menu::Menu, menu_state::MainMenuState,
+};
+
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    config::Config,
+    ecs::Entity,
+    prelude::{Builder, GameData, SimpleState, SimpleTrans, StateData, Trans},
+    ui::{get_default_font, Anchor, FontAsset, UiText, UiTransform},
+    StateEvent,
+};
+
+use std::path::PathBuf;
+
+const PLAY_AGAIN_ITEM: usize = 0;
+
+/// Shows the score of the round that just ended, and the high scores.
+pub struct ResultsState {
+    score: u32,
+    level: PathBuf,
+    scores_text: Option<Entity>,
+    menu: Option<Menu>,
+}
+
+impl ResultsState {
+    pub fn new(score: u32, level: PathBuf) -> Self {
+        ResultsState {
+            score,
+            level,
+            scores_text: None,
+            menu: None,
+        }
+    }
+}
+
+impl SimpleState for ResultsState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let mut high_scores = HighScores::load(HighScores::path());
+        let rank = high_scores.submit(self.score);
+        if rank.is_some() {
+            high_scores.save();
+        }
+
+        let mut text = format!("Score: {}\n", self.score);
+        if let Some(rank) = rank {
+            text += &format!("New high score! #{}\n", rank + 1);
+        }
+        text += "\nHigh scores\n";
+        for (i, score) in high_scores.scores.iter().enumerate() {
+            text += &format!("{}. {}\n", i + 1, score);
+        }
+
+        let font = {
+            let loader = data.world.read_resource::<Loader>();
+            let font_storage = data.world.read_resource::<AssetStorage<FontAsset>>();
+            get_default_font(&loader, &font_storage)
+        };
+
+        self.scores_text = Some(
+            data.world
+                .create_entity()
+                .with(UiTransform::new(
+                    "results".to_string(),
+                    Anchor::TopMiddle,
+                    Anchor::TopMiddle,
+                    0.0,
+                    -20.0,
+                    1.0,
+                    400.0,
+                    300.0,
+                ))
+                .with(UiText::new(font, text, [1.0, 1.0, 1.0, 1.0], 20.0))
+                .build(),
+        );
+
+        self.menu = Some(Menu::new(
+            data.world,
+            "Round over",
+            &["Play again".to_string(), "Main menu".to_string()],
+        ));
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        if let Some(text) = self.scores_text.take() {
+            data.world.delete_entity(text).unwrap();
+        }
+        if let Some(mut menu) = self.menu.take() {
+            menu.clear(data.world);
+        }
+    }
+
+    fn handle_event(
+        &mut self,
+        _data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        match self.menu.as_ref().and_then(|menu| menu.clicked_item(&event)) {
+            Some(PLAY_AGAIN_ITEM) => Trans::Switch(Box::new(
+                CubeGameState::new().with_level(self.level.clone()),
+            )),
+            Some(_) => Trans::Switch(Box::new(MainMenuState::new())),
+            None => Trans::None,
+        }
+    }
+}
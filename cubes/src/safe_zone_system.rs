@@ -1,23 +1,19 @@
 
-use crate::components::*;
+use crate::{audio::SoundEvent, components::*, game_mode::GameMode, physics_cleanup::PendingDeletions, physics_tag_index::PhysicsTagIndex, transform_utils::*};
 
 use amethyst::{
-    core::Time,
+    core::{math::Vector3, shrev::EventChannel, Time, Transform},
     ecs::{
-        Join, System, Entities, ReadStorage, WriteStorage, ReadExpect,
+        Join, LazyUpdate, System, Entities, Read, ReadStorage, Write, WriteStorage, ReadExpect, WriteExpect,
     },
     phythyst::{
         servers::*,
         objects::*,
     },
-    assets::{
-        Handle,
-    },
-    renderer::{
-        mtl::Material,
-    }
 };
 
+use std::collections::HashSet;
+
 pub struct SafeZoneSystem;
 
 impl SafeZoneSystem {
@@ -36,20 +32,39 @@ impl<'s> System<'s> for SafeZoneSystem {
         Entities<'s>,
         ReadExpect<'s, Time>,
         ReadExpect<'s, AreaPhysicsServer>,
-        ReadExpect<'s, SafeZoneAssets>,
+        Read<'s, PhysicsTagIndex>,
+        Option<WriteExpect<'s, GameMode>>,
+        Write<'s, EventChannel<SoundEvent>>,
+        Read<'s, LazyUpdate>,
+        Write<'s, PendingDeletions>,
         ReadStorage<'s, PhysicsAreaTag>,
         ReadStorage<'s, PhysicsBodyTag>,
         ReadStorage<'s, Bullet>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, ColliderShape>,
+        WriteStorage<'s, SweptMotion>,
         WriteStorage<'s, SafeZone>,
-        WriteStorage<'s, Handle<Material>>,
+        WriteStorage<'s, MaterialAnimation>,
     );
 
-    fn run(&mut self, (entities, time, area_server, safe_zone_assets, areas, bodies, bullets, mut safe_zones, mut mats): Self::SystemData) {
+    fn run(&mut self, (entities, time, area_server, tag_index, mut game_mode, mut sound_events, lazy, mut pending, areas, bodies, bullets, transforms, shapes, mut swept_motions, mut safe_zones, mut animations): Self::SystemData) {
 
-        let mut overlapped_bodies = Vec::<PhysicsBodyTag>::new();
+        let mut overlapped_bodies = HashSet::<PhysicsBodyTag>::new();
+
+        // The path travelled by the fast bodies during the last frame.
+        let mut sweeps = Vec::<(PhysicsBodyTag, Vector3<f32>, Vector3<f32>)>::new();
+        for (body, transform, swept_motion) in (&bodies, &transforms, &mut swept_motions).join() {
+            let position = translation_f32(transform);
+            if let Some(last_position) = swept_motion.last_position {
+                sweeps.push((*body, last_position, position));
+            }
+            swept_motion.last_position = Some(position);
+        }
 
         // Check if there are overlaps
-        for (area, safe_zone, mat) in (&areas, &mut safe_zones, &mut mats).join() {
+        for (area, transform, shape, safe_zone, animation) in (&areas, &transforms, &shapes, &mut safe_zones, (&mut animations).maybe()).join() {
+
+            let was_active = safe_zone.activation_timer > 0.0;
 
             let events = area_server.0.overlap_events(*area);
 
@@ -58,28 +73,68 @@ impl<'s> System<'s> for SafeZoneSystem {
                     OverlapEvent::Enter(body) => {
                         safe_zone.activation_timer = 2.0;
 
-                        overlapped_bodies.push(body);
+                        overlapped_bodies.insert(body);
                     }
                     _ => {}
                 }
             }
 
-            safe_zone.activation_timer -= time.delta_seconds();
+            // Catch the bodies that crossed the area in a single step.
+            if let ShapeDesc::Sphere { radius } = shape.desc {
+                let center = translation_f32(transform);
+                for (body, from, to) in sweeps.iter() {
+                    if !overlapped_bodies.contains(body) && segment_hits_sphere(from, to, &center, radius) {
+                        safe_zone.activation_timer = 2.0;
 
-            let safe_zone_mat = if safe_zone.activation_timer > 0.0 {
-                safe_zone_assets.active.clone()
-            }else{
-                safe_zone_assets.idle.clone()
-            };
+                        overlapped_bodies.insert(*body);
+                    }
+                }
+            }
 
-            *mat = safe_zone_mat;
+            if !was_active && safe_zone.activation_timer > 0.0 {
+                sound_events.single_write(SoundEvent::SafeZoneActivated);
+            }
+
+            safe_zone.activation_timer -= time.delta_seconds();
+
+            if let Some(animation) = animation {
+                animation.active = safe_zone.activation_timer > 0.0;
+            }
         }
 
         // Delete the bullets that overlaps
-        for (entity, body, bullet) in (&*entities, &bodies, &bullets).join() {
-            if overlapped_bodies.contains(body) {
-                entities.delete(entity);
+        for body in overlapped_bodies {
+            if let Some(entity) = tag_index.body_entity(body) {
+                if entities.is_alive(entity) && bullets.contains(entity) && !pending.contains(entity) {
+                    if let Some(game_mode) = game_mode.as_mut() {
+                        let points = game_mode.config.safe_zone_points;
+                        game_mode.register_hit(points);
+                    }
+
+                    if let Some(transform) = transforms.get(entity) {
+                        let mut emitter_transform = Transform::default();
+                        emitter_transform.set_translation(*transform.translation());
+                        lazy.create_entity(&entities)
+                            .with(emitter_transform)
+                            .with(ParticleEmitter::absorption())
+                            .build();
+                    }
+
+                    pending.delete(entity);
+                }
             }
         }
     }
-}
\ No newline at end of file
+}
+
+fn segment_hits_sphere(from: &Vector3<f32>, to: &Vector3<f32>, center: &Vector3<f32>, radius: f32) -> bool {
+    let segment = to - from;
+    let length_squared = segment.norm_squared();
+    let t = if length_squared > 0.0 {
+        ((center - from).dot(&segment) / length_squared).max(0.0).min(1.0)
+    } else {
+        0.0
+    };
+    let closest = from + segment * t;
+    (center - closest).norm_squared() <= radius * radius
+}
@@ -0,0 +1,293 @@
+use crate::{audio::AudioSettings, menu::Menu, menu_state::MainMenuState};
+
+use amethyst::{
+    config::Config,
+    input::{Bindings, Button, InputHandler, StringBindings, VirtualKeyCode},
+    prelude::{GameData, SimpleState, SimpleTrans, StateData, Trans},
+    window::DisplayConfig,
+    winit::{ElementState, Event, KeyboardInput, WindowEvent},
+    StateEvent,
+};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+
+/// The default input bindings; the player changes are saved in the `UserBindings`.
+pub const BINDINGS_PATH: &str = "res/bindings_config.ron";
+
+/// The actions rebound by the player, loaded over the default bindings.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserBindings {
+    pub actions: HashMap<String, Vec<Vec<Button>>>,
+}
+
+impl UserBindings {
+    pub fn path() -> String {
+        crate::get_dir_path("/configs/user_bindings.ron")
+    }
+
+    /// Loads the player changes; there are none until the first rebinding.
+    fn load_saved() -> Self {
+        let path = UserBindings::path();
+        if std::path::Path::new(&path).exists() {
+            UserBindings::load(path)
+        } else {
+            UserBindings::default()
+        }
+    }
+
+    /// The default bindings with the player changes applied.
+    pub fn load_bindings() -> Bindings<StringBindings> {
+        let mut bindings = Bindings::<StringBindings>::load(BINDINGS_PATH);
+        UserBindings::load_saved().apply(&mut bindings);
+        bindings
+    }
+
+    fn apply(&self, bindings: &mut Bindings<StringBindings>) {
+        for (action, combos) in self.actions.iter() {
+            let old_bindings: Vec<Vec<Button>> = bindings
+                .action_bindings(action)
+                .map(|combo| combo.to_vec())
+                .collect();
+            for combo in old_bindings.iter() {
+                bindings.remove_action_binding(action, combo);
+            }
+            for combo in combos {
+                if let Err(e) = bindings.insert_action_binding(action.clone(), combo.clone()) {
+                    log::error!("Can't bind {:?} to {}: {:?}", combo, action, e);
+                }
+            }
+        }
+    }
+}
+
+const WINDOW_SIZES: [(u32, u32); 4] = [(500, 500), (800, 800), (1280, 720), (1920, 1080)];
+
+const FULLSCREEN_ITEM: usize = 0;
+const WINDOW_SIZE_ITEM: usize = 1;
+const VOLUME_ITEM: usize = 2;
+const BINDINGS_ITEM: usize = 3;
+
+/// Edits the display, audio and input bindings.
+/// The display changes are applied at the next start.
+pub struct SettingsState {
+    menu: Option<Menu>,
+    showing_bindings: bool,
+    /// The actions listed in the bindings page, in the order of the items.
+    actions: Vec<String>,
+    /// The action that takes the next key pressed.
+    rebinding: Option<usize>,
+    display: DisplayConfig,
+    audio: AudioSettings,
+}
+
+impl SettingsState {
+    pub fn new() -> Self {
+        SettingsState {
+            menu: None,
+            showing_bindings: false,
+            actions: Vec::new(),
+            rebinding: None,
+            display: DisplayConfig::load(Self::display_path()),
+            audio: AudioSettings::load(AudioSettings::path()),
+        }
+    }
+
+    fn display_path() -> String {
+        crate::get_dir_path("/configs/display_conf.ron")
+    }
+
+    fn items(&self) -> Vec<String> {
+        let (width, height) = self.display.dimensions.unwrap_or(WINDOW_SIZES[0]);
+        vec![
+            format!("Fullscreen: {}", if self.display.fullscreen { "on" } else { "off" }),
+            format!("Window size: {}x{}", width, height),
+            format!("Volume: {:.0}%", self.audio.master_volume * 100.0),
+            "Bindings".to_string(),
+            "Back".to_string(),
+        ]
+    }
+
+    fn show(&mut self, data: &mut StateData<'_, GameData<'_, '_>>, bindings: bool) {
+        if let Some(mut menu) = self.menu.take() {
+            menu.clear(data.world);
+        }
+
+        let menu = if bindings {
+            let items = {
+                let input = data.world.read_resource::<InputHandler<StringBindings>>();
+                self.actions = input.bindings.actions().cloned().collect();
+                self.actions.sort();
+
+                let mut items: Vec<String> = self
+                    .actions
+                    .iter()
+                    .map(|action| binding_text(&input, action))
+                    .collect();
+                items.push("Back".to_string());
+                items
+            };
+            Menu::new(data.world, "Bindings: click an action, then press a key", &items)
+        } else {
+            let items = self.items();
+            Menu::new(data.world, "Settings", &items)
+        };
+
+        self.menu = Some(menu);
+        self.showing_bindings = bindings;
+    }
+
+    /// Binds `key` to the action in place of its current buttons, and saves it in the `UserBindings`.
+    fn rebind(&self, data: &mut StateData<'_, GameData<'_, '_>>, action: &str, key: VirtualKeyCode) {
+        let mut input = data.world.write_resource::<InputHandler<StringBindings>>();
+        let action = action.to_string();
+
+        let old_bindings: Vec<Vec<Button>> = input
+            .bindings
+            .action_bindings(&action)
+            .map(|combo| combo.to_vec())
+            .collect();
+        for combo in old_bindings.iter() {
+            input.bindings.remove_action_binding(&action, combo);
+        }
+
+        if let Err(e) = input
+            .bindings
+            .insert_action_binding(action.clone(), vec![Button::Key(key)])
+        {
+            log::error!("Can't bind {:?} to {}: {:?}", key, action, e);
+            for combo in old_bindings {
+                let _ = input.bindings.insert_action_binding(action.clone(), combo);
+            }
+            return;
+        }
+
+        let mut user_bindings = UserBindings::load_saved();
+        user_bindings
+            .actions
+            .insert(action, vec![vec![Button::Key(key)]]);
+        if let Err(e) = user_bindings.write(UserBindings::path()) {
+            log::error!("Can't save the bindings: {}", e);
+        }
+    }
+
+    fn save(&self) {
+        if let Err(e) = self.display.write(Self::display_path()) {
+            log::error!("Can't save the display settings: {}", e);
+        }
+        if let Err(e) = self.audio.write(AudioSettings::path()) {
+            log::error!("Can't save the audio settings: {}", e);
+        }
+    }
+}
+
+impl SimpleState for SettingsState {
+    fn on_start(&mut self, mut data: StateData<'_, GameData<'_, '_>>) {
+        self.show(&mut data, false);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        if let Some(mut menu) = self.menu.take() {
+            menu.clear(data.world);
+        }
+    }
+
+    fn handle_event(
+        &mut self,
+        mut data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        if let Some(index) = self.rebinding {
+            match pressed_key(&event) {
+                Some(VirtualKeyCode::Escape) => {}
+                Some(key) => {
+                    let action = self.actions[index].clone();
+                    self.rebind(&mut data, &action, key);
+                }
+                None => return Trans::None,
+            }
+            self.rebinding = None;
+            self.show(&mut data, true);
+            return Trans::None;
+        }
+
+        let clicked = match self.menu.as_ref().and_then(|menu| menu.clicked_item(&event)) {
+            Some(clicked) => clicked,
+            None => return Trans::None,
+        };
+
+        if self.showing_bindings {
+            if clicked < self.actions.len() {
+                self.rebinding = Some(clicked);
+                let text = format!("{}: press a key (Escape cancels)", self.actions[clicked]);
+                self.menu.as_ref().unwrap().set_item_text(data.world, clicked, text);
+            } else {
+                self.show(&mut data, false);
+            }
+            return Trans::None;
+        }
+
+        match clicked {
+            FULLSCREEN_ITEM => self.display.fullscreen = !self.display.fullscreen,
+            WINDOW_SIZE_ITEM => {
+                let current = self.display.dimensions.unwrap_or(WINDOW_SIZES[0]);
+                let next = WINDOW_SIZES
+                    .iter()
+                    .position(|size| *size == current)
+                    .map_or(0, |i| (i + 1) % WINDOW_SIZES.len());
+                self.display.dimensions = Some(WINDOW_SIZES[next]);
+            }
+            VOLUME_ITEM => {
+                let volume = (self.audio.master_volume * 10.0).round() as u32;
+                self.audio.master_volume = ((volume + 1) % 11) as f32 / 10.0;
+                data.world.add_resource(self.audio.clone());
+            }
+            BINDINGS_ITEM => {
+                self.show(&mut data, true);
+                return Trans::None;
+            }
+            _ => {
+                self.save();
+                return Trans::Switch(Box::new(MainMenuState::new()));
+            }
+        }
+
+        let items = self.items();
+        let menu = self.menu.as_ref().unwrap();
+        for (i, item) in items.into_iter().enumerate() {
+            menu.set_item_text(data.world, i, item);
+        }
+
+        Trans::None
+    }
+}
+
+fn binding_text(input: &InputHandler<StringBindings>, action: &String) -> String {
+    let buttons: Vec<String> = input
+        .bindings
+        .action_bindings(action)
+        .map(|combo| format!("{:?}", combo))
+        .collect();
+    format!("{}: {}", action, buttons.join(", "))
+}
+
+fn pressed_key(event: &StateEvent) -> Option<VirtualKeyCode> {
+    match event {
+        StateEvent::Window(Event::WindowEvent {
+            event:
+                WindowEvent::KeyboardInput {
+                    input:
+                        KeyboardInput {
+                            state: ElementState::Pressed,
+                            virtual_keycode: Some(key),
+                            ..
+                        },
+                    ..
+                },
+            ..
+        }) => Some(*key),
+        _ => None,
+    }
+}
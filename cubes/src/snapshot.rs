@@ -0,0 +1,169 @@
+use crate::{components::*, transform_utils::*};
+
+use amethyst::{
+    core::{
+        math::{Isometry3, Quaternion, Translation3, UnitQuaternion, Vector3},
+        Transform,
+    },
+    ecs::{prelude::World, Entity, Join},
+    phythyst::{objects::*, servers::*},
+};
+
+use serde::{Deserialize, Serialize};
+
+use std::{fs, io, path::Path};
+
+/// The state of a rigid body.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BodySnapshot {
+    pub translation: [f32; 3],
+    /// Quaternion as `[i, j, k, w]`.
+    pub rotation: [f32; 4],
+    pub linear_velocity: [f32; 3],
+    pub angular_velocity: [f32; 3],
+    pub sleeping: bool,
+    /// The `SweptMotion` of the fast bodies, so the next sweep starts from the same point.
+    #[serde(default)]
+    pub swept_last_position: Option<[f32; 3]>,
+}
+
+impl BodySnapshot {
+    pub fn transform(&self) -> Transform {
+        let t = &self.translation;
+        let r = &self.rotation;
+        transform_from_isometry(&Isometry3::from_parts(
+            Translation3::new(t[0], t[1], t[2]),
+            UnitQuaternion::new_unchecked(Quaternion::new(r[3], r[0], r[1], r[2])),
+        ))
+    }
+
+    pub fn linear_velocity(&self) -> Vector3<f32> {
+        Vector3::from(self.linear_velocity)
+    }
+
+    pub fn angular_velocity(&self) -> Vector3<f32> {
+        Vector3::from(self.angular_velocity)
+    }
+}
+
+/// Everything needed to bring the cubes arena back to a captured moment.
+///
+/// The bodies spawned by the level are matched through their `SnapshotId`,
+/// while the bullets are deleted and spawned again.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PhysicsSnapshot {
+    pub bodies: Vec<(u32, BodySnapshot)>,
+    pub bullets: Vec<BodySnapshot>,
+    pub safe_zones: Vec<(u32, f32)>,
+}
+
+impl PhysicsSnapshot {
+    pub fn capture(world: &World) -> Self {
+        let body_server = world.read_resource::<RBodyPhysicsServer<f32>>();
+        let transforms = world.read_storage::<Transform>();
+        let bodies = world.read_storage::<PhysicsBodyTag>();
+        let ids = world.read_storage::<SnapshotId>();
+        let bullets = world.read_storage::<Bullet>();
+        let safe_zones = world.read_storage::<SafeZone>();
+        let swept_motions = world.read_storage::<SweptMotion>();
+
+        let mut snapshot = PhysicsSnapshot::default();
+
+        for (id, body, transform, swept) in (&ids, &bodies, &transforms, swept_motions.maybe()).join() {
+            snapshot
+                .bodies
+                .push((id.0, capture_body(&body_server, *body, transform, swept)));
+        }
+
+        for (_, body, transform, swept) in (&bullets, &bodies, &transforms, swept_motions.maybe()).join() {
+            snapshot
+                .bullets
+                .push(capture_body(&body_server, *body, transform, swept));
+        }
+
+        for (id, safe_zone) in (&ids, &safe_zones).join() {
+            snapshot.safe_zones.push((id.0, safe_zone.activation_timer));
+        }
+
+        snapshot
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let data = ron::ser::to_string_pretty(self, Default::default())
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        if let Some(dir) = path.as_ref().parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, data)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let data = fs::read_to_string(path)?;
+        ron::de::from_str(&data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Restores the bodies and the safe zones that have a `SnapshotId`.
+    /// The bullets must be spawned again by the caller.
+    pub fn restore_level(&self, world: &mut World) {
+        let entities: Vec<(u32, Entity)> = {
+            let entities = world.entities();
+            let ids = world.read_storage::<SnapshotId>();
+            (&entities, &ids).join().map(|(e, id)| (id.0, e)).collect()
+        };
+        let entity_of = |id: u32| entities.iter().find(|(i, _)| *i == id).map(|(_, e)| *e);
+
+        for (id, state) in self.bodies.iter() {
+            match entity_of(*id) {
+                Some(entity) => restore_body(world, entity, state),
+                None => log::warn!("The snapshot body {} doesn't exist in this level.", id),
+            }
+        }
+
+        let mut safe_zones = world.write_storage::<SafeZone>();
+        for (id, activation_timer) in self.safe_zones.iter() {
+            if let Some(safe_zone) = entity_of(*id).and_then(|e| safe_zones.get_mut(e)) {
+                safe_zone.activation_timer = *activation_timer;
+            }
+        }
+    }
+}
+
+fn capture_body(
+    body_server: &RBodyPhysicsServer<f32>,
+    body: PhysicsBodyTag,
+    transform: &Transform,
+    swept: Option<&SweptMotion>,
+) -> BodySnapshot {
+    let rotation = rotation_f32(transform);
+    let q = rotation.quaternion();
+    BodySnapshot {
+        translation: translation_f32(transform).into(),
+        rotation: [q.i, q.j, q.k, q.w],
+        linear_velocity: body_server.linear_velocity(body).into(),
+        angular_velocity: body_server.angular_velocity(body).into(),
+        sleeping: body_server.is_sleeping(body),
+        swept_last_position: swept.and_then(|s| s.last_position).map(Into::into),
+    }
+}
+
+/// Moves the body of the entity to the captured state.
+pub fn restore_body(world: &mut World, entity: Entity, state: &BodySnapshot) {
+    let transform = state.transform();
+
+    if let Some(body) = world.read_storage::<PhysicsBodyTag>().get(entity) {
+        let mut body_server = world.write_resource::<RBodyPhysicsServer<f32>>();
+        body_server.set_body_transform(*body, &transform);
+        body_server.set_linear_velocity(*body, &state.linear_velocity());
+        body_server.set_angular_velocity(*body, &state.angular_velocity());
+        body_server.set_sleeping(*body, state.sleeping);
+    }
+
+    if let Some(swept) = world.write_storage::<SweptMotion>().get_mut(entity) {
+        swept.last_position = state.swept_last_position.map(Vector3::from);
+    }
+
+    world
+        .write_storage::<Transform>()
+        .insert(entity, transform)
+        .unwrap();
+}
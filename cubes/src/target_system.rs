@@ -1,28 +1,71 @@
-use crate::components::*;
+use crate::{
+    components::*,
+    contact_event_system::{ContactEvent, ContactEventKind},
+    game_mode::GameMode,
+    physics_cleanup::PendingDeletions,
+};
 
 use amethyst::{
-    core::Time,
+    core::shrev::{EventChannel, ReaderId},
     ecs::{
-        Join, System, Entities, ReadStorage, WriteStorage, ReadExpect,
-    },
-    phythyst::{
-        servers::*,
-        objects::*,
+        Entities, Read, ReadStorage, Resources, System, SystemData, Write, WriteExpect,
     },
-    assets::{
-        Handle,
-    },
-    renderer::{
-        mtl::Material,
-    }
 };
 
-pub struct TargetSystem;
+/// Scores the bullets that hit a `Target`, and removes them.
+pub struct TargetSystem {
+    contact_reader: Option<ReaderId<ContactEvent>>,
+}
 
-impl TargetSystem{
+impl TargetSystem {
     pub fn new() -> Self {
-        TargetSystem{
+        TargetSystem {
+            contact_reader: None,
+        }
+    }
+}
+
+impl<'s> System<'s> for TargetSystem {
+    type SystemData = (
+        Entities<'s>,
+        Read<'s, EventChannel<ContactEvent>>,
+        Option<WriteExpect<'s, GameMode>>,
+        Write<'s, PendingDeletions>,
+        ReadStorage<'s, Target>,
+        ReadStorage<'s, Bullet>,
+    );
+
+    fn run(&mut self, (entities, contacts, mut game_mode, mut pending, targets, bullets): Self::SystemData) {
+        for event in contacts.read(self.contact_reader.as_mut().unwrap()) {
+            if event.kind != ContactEventKind::Began || !targets.contains(event.entity) {
+                continue;
+            }
+
+            let bullet = match event.other {
+                Some(other)
+                    if bullets.contains(other)
+                        && entities.is_alive(other)
+                        && !pending.contains(other) =>
+                {
+                    other
+                }
+                _ => continue,
+            };
 
+            if let Some(game_mode) = game_mode.as_mut() {
+                let points = game_mode.config.target_points;
+                game_mode.register_hit(points);
+            }
+
+            pending.delete(bullet);
         }
     }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.contact_reader = Some(
+            res.fetch_mut::<EventChannel<ContactEvent>>()
+                .register_reader(),
+        );
+    }
 }
@@ -0,0 +1,41 @@
+use amethyst::core::{
+    math::{Isometry3, Point3, Quaternion, UnitQuaternion, Vector3},
+    Transform,
+};
+
+/// Returns the translation of the transform converted to `f32`.
+pub fn translation_f32(transform: &Transform) -> Vector3<f32> {
+    let t = transform.translation();
+    Vector3::new(t.x.into(), t.y.into(), t.z.into())
+}
+
+/// Returns the position of the transform as a point, converted to `f32`.
+pub fn position_f32(transform: &Transform) -> Point3<f32> {
+    Point3::from(translation_f32(transform))
+}
+
+/// Returns the rotation of the transform converted to `f32`.
+pub fn rotation_f32(transform: &Transform) -> UnitQuaternion<f32> {
+    let q = transform.rotation().quaternion();
+    UnitQuaternion::from_quaternion(Quaternion::new(
+        q.w.into(),
+        q.i.into(),
+        q.j.into(),
+        q.k.into(),
+    ))
+}
+
+/// Creates a `Transform` from an isometry expressed in `f32`.
+pub fn transform_from_isometry(isometry: &Isometry3<f32>) -> Transform {
+    let mut transform = Transform::default();
+    let t = isometry.translation.vector;
+    let q = isometry.rotation.quaternion();
+    transform.set_translation_xyz(t.x, t.y, t.z);
+    transform.set_rotation(UnitQuaternion::new_unchecked(Quaternion::new(
+        q.w.into(),
+        q.i.into(),
+        q.j.into(),
+        q.k.into(),
+    )));
+    transform
+}
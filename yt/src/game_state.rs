@@ -1,3 +1,11 @@
+use crate::{
+    lighting,
+    picking_system::{PickPoints, Score},
+    prefab::{PrefabInstance, SpawnPrefab},
+    rules::{GameRules, RulesConfig},
+    scene::SceneDesc,
+};
+
 use amethyst::{
     assets::{AssetStorage, Handle, Loader},
     core::{
@@ -9,13 +17,9 @@ use amethyst::{
     input::{InputEvent, InputHandler, StringBindings},
     prelude::*,
     renderer::{
-        camera, light, mtl,
-        palette::{LinSrgba, Srgb},
-        rendy::{
-            mesh::{Normal, Position, Tangent, TexCoord},
-            texture,
-        },
-        shape::Shape,
+        camera, mtl,
+        palette::LinSrgba,
+        rendy::texture,
         types, Transparent,
     },
     window::ScreenDimensions,
@@ -24,6 +28,8 @@ use amethyst::{
 
 use rand::prelude::*;
 
+use std::collections::HashMap;
+
 #[derive(Default)]
 pub struct LoadingState {
     counter: i32,
@@ -33,11 +39,21 @@ impl SimpleState for LoadingState {
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         println!("Loading on start");
 
+        data.world.register::<PrefabInstance>();
         data.world.add_resource(MyHandleStorage::default());
+        data.world
+            .add_resource(GameRules::new(RulesConfig::load("./configs/rules.ron")));
+        data.world.add_resource(Score::default());
 
         add_camera_entity(data.world);
-        add_light_entity(data.world, Vector3::new(-1.0, -1.0, -1.0));
-        add_sphere_entity(data.world);
+        let scene = SceneDesc::load("./configs/scene.ron");
+        lighting::add_lights(data.world, &scene.lighting);
+        let first_prefab = scene.spawn_sequence.first().cloned();
+        data.world.add_resource(scene);
+
+        if let Some(prefab) = first_prefab {
+            spawn_prefab(data.world, &prefab);
+        }
     }
 
     fn on_stop(&mut self, _data: StateData<'_, GameData<'_, '_>>) {
@@ -59,7 +75,8 @@ impl SimpleState for LoadingState {
 #[derive(Default)]
 pub struct GamePlayState {
     time_bank: f32,
-    last_added_mesh_type: bool,
+    /// Position in the `SceneDesc::spawn_sequence`.
+    next_spawn: usize,
 }
 
 impl SimpleState for GamePlayState {
@@ -82,19 +99,72 @@ impl SimpleState for GamePlayState {
     fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
         self.time_bank += { data.world.read_resource::<Time>().delta_seconds() };
 
-        if self.time_bank > 1.0 {
-            self.time_bank -= 1.0;
-
-            if self.last_added_mesh_type {
-                add_cube_entity(data.world);
+        let (spawn_interval, prefab) = {
+            let scene = data.world.read_resource::<SceneDesc>();
+            let rules = data.world.read_resource::<GameRules>();
+            let prefab = if scene.spawn_sequence.is_empty() {
+                None
             } else {
-                add_sphere_entity(data.world);
+                Some(scene.spawn_sequence[self.next_spawn % scene.spawn_sequence.len()].clone())
+            };
+            (scene.spawn_interval / rules.spawn_rate_multiplier(), prefab)
+        };
+
+        if self.time_bank > spawn_interval {
+            self.time_bank -= spawn_interval;
+
+            if let Some(prefab) = prefab {
+                spawn_prefab(data.world, &prefab);
+                self.next_spawn += 1;
             }
+        }
 
-            self.last_added_mesh_type = !self.last_added_mesh_type;
+        if data.world.read_resource::<GameRules>().is_game_over() {
+            Trans::Switch(Box::new(GameOverState::default()))
+        } else {
+            Trans::None
         }
+    }
+}
 
-        Trans::None
+/// Waits for a click, then starts a new game.
+#[derive(Default)]
+pub struct GameOverState {
+    pick_was_down: bool,
+}
+
+impl SimpleState for GameOverState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        data.world.write_resource::<GameRules>().playing = false;
+
+        let score = data.world.read_resource::<Score>();
+        let rules = data.world.read_resource::<GameRules>();
+        println!(
+            "Game over! Score: {}, picked: {}, escaped: {}, level reached: {}. Click to play again.",
+            score.points, score.picked, rules.escaped, rules.level
+        );
+        // The click that was in progress doesn't restart the game.
+        self.pick_was_down = true;
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        data.world.delete_all();
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let pick_is_down = data
+            .world
+            .read_resource::<InputHandler<StringBindings>>()
+            .action_is_down("pick")
+            .unwrap_or(false);
+        let clicked = pick_is_down && !self.pick_was_down;
+        self.pick_was_down = pick_is_down;
+
+        if clicked {
+            Trans::Switch(Box::new(LoadingState::default()))
+        } else {
+            Trans::None
+        }
     }
 }
 
@@ -143,114 +213,70 @@ fn add_camera_entity(world: &mut World) {
         .build();
 }
 
-fn add_light_entity(world: &mut World, direction: Vector3<f32>) {
-    let mut light_transform = Transform::default();
-    light_transform.set_translation(Vector3::new(6.0, 6.0, 6.0));
-
-    let light: light::Light = light::DirectionalLight {
-        color: Srgb::new(1.0, 1.0, 1.0),
-        direction: direction.normalize(),
-        intensity: 5.0,
-    }
-    .into();
-
-    world
-        .create_entity()
-        .with(light)
-        .with(light_transform)
-        .build();
-}
-
-fn add_sphere_entity(world: &mut World) {
-    let mesh = {
-        let mut my_handle_storage = world.write_resource::<MyHandleStorage>();
-
-        my_handle_storage
-            .sphere_mesh
-            .get_or_insert_with(|| {
-                let radius = 1.0;
-                let mesh_data: types::MeshData = Shape::Sphere(32, 32)
-                    .generate::<(Vec<Position>, Vec<Normal>, Vec<Tangent>, Vec<TexCoord>)>(Some((
-                        radius, radius, radius,
-                    )))
-                    .into();
-
-                create_mesh(world, mesh_data)
-            })
-            .clone()
-    };
-
-    let mat = {
-        let mut my_handle_storage = world.write_resource::<MyHandleStorage>();
-
-        my_handle_storage
-            .material
-            .get_or_insert_with(|| {
-                create_material(world, LinSrgba::new(1.0, 1.0, 1.0, 1.0), 0.0, 1.0)
-            })
-            .clone()
+/// Spawns an entity described by the prefab `name` of the `SceneDesc`.
+pub fn spawn_prefab(world: &mut World, name: &str) -> Option<Entity> {
+    let prefab = match world.read_resource::<SceneDesc>().prefabs.get(name) {
+        Some(prefab) => prefab.clone(),
+        None => {
+            log::error!("The prefab {} doesn't exist.", name);
+            return None;
+        }
     };
 
-    let mut sphere_transform = Transform::default();
+    let (mesh, mat) = prefab_handles(world, name, &prefab);
 
     let mut rng = rand::thread_rng();
-    sphere_transform.set_translation_x(rng.gen_range(-3.0, 3.0));
-    sphere_transform.set_translation_y(rng.gen_range(-3.0, 3.0));
-    sphere_transform.set_translation_z(rng.gen_range(-3.0, 3.0));
+    let mut transform = Transform::default();
+    transform.set_translation(prefab.sample_position(&mut rng));
 
-    world
+    let speed_multiplier = world.read_resource::<GameRules>().speed_multiplier();
+
+    let mut builder = world
         .create_entity()
-        .with(sphere_transform)
+        .with(transform)
         .with(mesh)
         .with(mat)
-        .with(Motion::new(-1.0 * (2.0 + 10.0 * rng.gen::<f32>())))
-        .build();
-}
+        .with(PrefabInstance {
+            prefab: name.to_string(),
+            tags: prefab.tags.clone(),
+        });
 
-fn add_cube_entity(world: &mut World) {
-    let mesh = {
-        let mut my_handle_storage = world.write_resource::<MyHandleStorage>();
-
-        my_handle_storage
-            .cube_mesh
-            .get_or_insert_with(|| {
-                let radius = 1.0;
-                let mesh_data: types::MeshData = Shape::Cube
-                    .generate::<(Vec<Position>, Vec<Normal>, Vec<Tangent>, Vec<TexCoord>)>(Some((
-                        radius, radius, radius,
-                    )))
-                    .into();
-
-                create_mesh(world, mesh_data)
-            })
-            .clone()
-    };
-
-    let mat = {
-        let mut my_handle_storage = world.write_resource::<MyHandleStorage>();
+    if let Some(speed) = prefab.motion {
+        builder = builder.with(Motion::new(speed.sample(&mut rng) * speed_multiplier));
+    }
+    if prefab.pickable {
+        builder = builder
+            .with(prefab.pick_bounds())
+            .with(PickPoints(prefab.points));
+    }
 
-        my_handle_storage
-            .material
-            .get_or_insert_with(|| {
-                create_material(world, LinSrgba::new(1.0, 1.0, 1.0, 1.0), 0.0, 1.0)
-            })
-            .clone()
-    };
+    Some(builder.build())
+}
 
-    let mut sphere_transform = Transform::default();
+/// Returns the mesh and material of the prefab, creating them the first time.
+fn prefab_handles(
+    world: &mut World,
+    name: &str,
+    prefab: &SpawnPrefab,
+) -> (Handle<types::Mesh>, Handle<mtl::Material>) {
+    if let Some(handles) = world.read_resource::<MyHandleStorage>().prefabs.get(name) {
+        return handles.clone();
+    }
 
-    let mut rng = rand::thread_rng();
-    sphere_transform.set_translation_x(rng.gen_range(-3.0, 3.0));
-    sphere_transform.set_translation_y(rng.gen_range(-3.0, 3.0));
-    sphere_transform.set_translation_z(rng.gen_range(-3.0, 3.0));
+    let mesh = create_mesh(world, prefab.shape.mesh_data(prefab.scale));
+    let [r, g, b, a] = prefab.color;
+    let mat = create_material(
+        world,
+        LinSrgba::new(r, g, b, a),
+        prefab.metallic,
+        prefab.roughness,
+    );
 
     world
-        .create_entity()
-        .with(sphere_transform)
-        .with(mesh)
-        .with(mat)
-        .with(Motion::new(2.0 + 10.0 * rng.gen::<f32>()))
-        .build();
+        .write_resource::<MyHandleStorage>()
+        .prefabs
+        .insert(name.to_string(), (mesh.clone(), mat.clone()));
+    (mesh, mat)
 }
 
 pub fn create_mesh(world: &World, mesh_data: types::MeshData) -> Handle<types::Mesh> {
@@ -302,11 +328,10 @@ pub fn create_material(
     material
 }
 
+/// The handles shared by the entities of each prefab.
 #[derive(Default)]
 struct MyHandleStorage {
-    pub sphere_mesh: Option<Handle<types::Mesh>>,
-    pub cube_mesh: Option<Handle<types::Mesh>>,
-    pub material: Option<Handle<mtl::Material>>,
+    pub prefabs: HashMap<String, (Handle<types::Mesh>, Handle<mtl::Material>)>,
 }
 
 pub struct Motion {
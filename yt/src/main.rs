@@ -1,14 +1,21 @@
 mod game_state;
+#[path = "../../common/lighting.rs"]
+mod lighting;
+mod picking_system;
+mod prefab;
 mod render_graph;
+mod rules;
+mod scene;
 mod sphere_system;
 
-use crate::sphere_system::*;
+use crate::{picking_system::PickingSystem, rules::RulesSystem, sphere_system::*};
 use game_state::*;
 use render_graph::*;
 
 use amethyst::{
     config::Config,
     core::transform::bundle::TransformBundle,
+    input::{InputBundle, StringBindings},
     renderer::{types::DefaultBackend, RenderingSystem},
     window::{DisplayConfig, WindowBundle},
     Application, GameDataBuilder, Logger,
@@ -26,10 +33,16 @@ fn main() -> amethyst::Result<()> {
     let game_data = GameDataBuilder::default()
         .with_bundle(WindowBundle::from_config(display_config))?
         .with_bundle(TransformBundle::new())?
+        .with_bundle(
+            InputBundle::<StringBindings>::new()
+                .with_bindings_from_file("./configs/bindings_config.ron")?,
+        )?
         .with_thread_local(RenderingSystem::<DefaultBackend, _>::new(
             MyRenderGraphCreator::default(),
         ))
-        .with(MotionSystem::default(), "MotionSystem", &[]);
+        .with(MotionSystem::default(), "MotionSystem", &[])
+        .with(PickingSystem::default(), "PickingSystem", &[])
+        .with(RulesSystem::default(), "RulesSystem", &["MotionSystem"]);
 
     let mut game =
         Application::build("./", game_state::LoadingState::default())?.build(game_data)?;
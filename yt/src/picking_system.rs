@@ -0,0 +1,252 @@
+use crate::{prefab::PrefabInstance, rules::GameRules};
+
+use amethyst::{
+    core::{
+        math::{Matrix4, Point3, Vector3, Vector4},
+        Transform,
+    },
+    ecs::{
+        Component, DenseVecStorage, Entities, Entity, Join, Read, ReadExpect, ReadStorage, System,
+        Write,
+    },
+    input::{InputHandler, StringBindings},
+    renderer::camera::Camera,
+    window::ScreenDimensions,
+};
+
+/// The volume that a click must hit to pick the entity, in its local space.
+#[derive(Clone, Debug)]
+pub enum PickBounds {
+    Sphere { radius: f32 },
+    Box { half_extents: Vector3<f32> },
+}
+
+impl Component for PickBounds {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Points given by picking this entity.
+pub struct PickPoints(pub u32);
+
+impl Component for PickPoints {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[derive(Debug, Default)]
+pub struct Score {
+    pub points: u32,
+    pub picked: u32,
+}
+
+/// On click, casts a ray from the camera through the cursor and destroys the
+/// closest entity whose `PickBounds` is hit. Clicks are ignored at game over.
+#[derive(Default)]
+pub struct PickingSystem {
+    pick_was_down: bool,
+}
+
+impl<'s> System<'s> for PickingSystem {
+    type SystemData = (
+        Entities<'s>,
+        Read<'s, InputHandler<StringBindings>>,
+        ReadExpect<'s, ScreenDimensions>,
+        Write<'s, Score>,
+        Option<Read<'s, GameRules>>,
+        ReadStorage<'s, Camera>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, PickBounds>,
+        ReadStorage<'s, PickPoints>,
+        ReadStorage<'s, PrefabInstance>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, input, screen, mut score, rules, cameras, transforms, bounds, points, instances): Self::SystemData,
+    ) {
+        let pick_is_down = input.action_is_down("pick").unwrap_or(false);
+        let clicked = pick_is_down && !self.pick_was_down;
+        self.pick_was_down = pick_is_down;
+
+        if !clicked || !rules.map_or(false, |rules| rules.playing) {
+            return;
+        }
+
+        let (x, y) = match input.mouse_position() {
+            Some(position) => position,
+            None => return,
+        };
+
+        let (camera, camera_transform) = match (&cameras, &transforms).join().next() {
+            Some(camera) => camera,
+            None => return,
+        };
+
+        let (origin, direction) = match screen_ray(camera, camera_transform, &screen, x as f32, y as f32) {
+            Some(ray) => ray,
+            None => return,
+        };
+
+        let mut closest: Option<(Entity, f32)> = None;
+        for (entity, transform, bounds) in (&*entities, &transforms, &bounds).join() {
+            let local = match global_matrix_f32(transform).try_inverse() {
+                Some(local) => local,
+                None => continue,
+            };
+            let local_origin = local.transform_point(&origin);
+            let local_direction = local.transform_vector(&direction);
+
+            let distance = match bounds {
+                PickBounds::Sphere { radius } => {
+                    ray_sphere(&local_origin, &local_direction, *radius)
+                }
+                PickBounds::Box { half_extents } => {
+                    ray_box(&local_origin, &local_direction, half_extents)
+                }
+            };
+
+            if let Some(distance) = distance {
+                if closest.map_or(true, |(_, d)| distance < d) {
+                    closest = Some((entity, distance));
+                }
+            }
+        }
+
+        if let Some((entity, _)) = closest {
+            score.points += points.get(entity).map_or(0, |p| p.0);
+            score.picked += 1;
+            log::info!(
+                "Picked {}, score: {}",
+                instances.get(entity).map_or("object", |i| i.prefab.as_str()),
+                score.points
+            );
+            entities.delete(entity).unwrap();
+        }
+    }
+}
+
+fn global_matrix_f32(transform: &Transform) -> Matrix4<f32> {
+    transform.global_matrix().map(|v| v.into())
+}
+
+/// Returns the origin and direction of the ray that starts at the camera and
+/// passes through the screen point.
+fn screen_ray(
+    camera: &Camera,
+    camera_transform: &Transform,
+    screen: &ScreenDimensions,
+    x: f32,
+    y: f32,
+) -> Option<(Point3<f32>, Vector3<f32>)> {
+    let camera_matrix = global_matrix_f32(camera_transform);
+    let view_projection = camera.as_matrix() * camera_matrix.try_inverse()?;
+    let inverse = view_projection.try_inverse()?;
+
+    // The projection flips Y, so the top of the screen is at -1 like the cursor.
+    let ndc_x = x / screen.width() * 2.0 - 1.0;
+    let ndc_y = y / screen.height() * 2.0 - 1.0;
+
+    let target = inverse * Vector4::new(ndc_x, ndc_y, 0.5, 1.0);
+    if target.w.abs() < std::f32::EPSILON {
+        return None;
+    }
+    let target = Point3::new(target.x, target.y, target.z) / target.w;
+
+    let origin = camera_matrix.transform_point(&Point3::origin());
+    Some((origin, (target - origin).normalize()))
+}
+
+/// Returns the ray parameter of the first hit; it's the same in local and world space.
+fn ray_sphere(origin: &Point3<f32>, direction: &Vector3<f32>, radius: f32) -> Option<f32> {
+    let o = origin.coords;
+    let a = direction.dot(direction);
+    let b = 2.0 * o.dot(direction);
+    let c = o.dot(&o) - radius * radius;
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let sqrt = discriminant.sqrt();
+    let near = (-b - sqrt) / (2.0 * a);
+    let far = (-b + sqrt) / (2.0 * a);
+    if far < 0.0 {
+        None
+    } else {
+        Some(near.max(0.0))
+    }
+}
+
+/// Slab test against the box centered in the origin.
+fn ray_box(origin: &Point3<f32>, direction: &Vector3<f32>, half_extents: &Vector3<f32>) -> Option<f32> {
+    let mut near = std::f32::NEG_INFINITY;
+    let mut far = std::f32::INFINITY;
+
+    for axis in 0..3 {
+        if direction[axis].abs() < std::f32::EPSILON {
+            if origin[axis].abs() > half_extents[axis] {
+                return None;
+            }
+            continue;
+        }
+
+        let t1 = (-half_extents[axis] - origin[axis]) / direction[axis];
+        let t2 = (half_extents[axis] - origin[axis]) / direction[axis];
+        near = near.max(t1.min(t2));
+        far = far.min(t1.max(t2));
+    }
+
+    if near > far || far < 0.0 {
+        None
+    } else {
+        Some(near.max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ray_hits_the_sphere_in_front() {
+        let origin = Point3::new(0.0, 0.0, -5.0);
+        let distance = ray_sphere(&origin, &Vector3::z(), 1.0).unwrap();
+        assert!((distance - 4.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn ray_misses_the_sphere() {
+        let origin = Point3::new(2.0, 0.0, -5.0);
+        assert_eq!(ray_sphere(&origin, &Vector3::z(), 1.0), None);
+        // Behind the origin.
+        let origin = Point3::new(0.0, 0.0, 5.0);
+        assert_eq!(ray_sphere(&origin, &Vector3::z(), 1.0), None);
+    }
+
+    #[test]
+    fn ray_from_inside_the_sphere_hits_at_once() {
+        assert_eq!(ray_sphere(&Point3::origin(), &Vector3::x(), 1.0), Some(0.0));
+    }
+
+    #[test]
+    fn ray_hits_the_box_in_front() {
+        let origin = Point3::new(0.5, 0.0, -5.0);
+        let half_extents = Vector3::new(1.0, 2.0, 1.0);
+        let distance = ray_box(&origin, &Vector3::z(), &half_extents).unwrap();
+        assert!((distance - 4.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn ray_misses_the_box() {
+        let half_extents = Vector3::new(1.0, 1.0, 1.0);
+        // Parallel to the z axis, outside the slab on x.
+        let origin = Point3::new(1.5, 0.0, -5.0);
+        assert_eq!(ray_box(&origin, &Vector3::z(), &half_extents), None);
+        // Diagonal, passing beside a corner.
+        let origin = Point3::new(-5.0, 0.0, -3.0);
+        let direction = Vector3::new(1.0, 0.0, 0.2).normalize();
+        assert_eq!(ray_box(&origin, &direction, &half_extents), None);
+        // Behind the origin.
+        let origin = Point3::new(0.0, 0.0, 5.0);
+        assert_eq!(ray_box(&origin, &Vector3::z(), &half_extents), None);
+    }
+}
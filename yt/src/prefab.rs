@@ -0,0 +1,168 @@
+use crate::picking_system::PickBounds;
+
+use amethyst::{
+    core::math::Vector3,
+    ecs::{Component, DenseVecStorage},
+    renderer::{
+        rendy::mesh::{Normal, Position, Tangent, TexCoord},
+        shape::Shape,
+        types,
+    },
+};
+
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashSet;
+
+/// Serializable version of the `Shape` used to generate meshes.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum MeshShape {
+    Cube,
+    Sphere(usize, usize),
+    Cylinder(usize),
+    Cone(usize),
+    IcoSphere(usize),
+}
+
+impl MeshShape {
+    pub fn mesh_data(&self, scale: [f32; 3]) -> types::MeshData {
+        let shape = match self {
+            MeshShape::Cube => Shape::Cube,
+            MeshShape::Sphere(u, v) => Shape::Sphere(*u, *v),
+            MeshShape::Cylinder(u) => Shape::Cylinder(*u, Some(1usize)),
+            MeshShape::Cone(u) => Shape::Cone(*u),
+            MeshShape::IcoSphere(subdivides) => Shape::IcoSphere(Some(*subdivides)),
+        };
+        shape
+            .generate::<(Vec<Position>, Vec<Normal>, Vec<Tangent>, Vec<TexCoord>)>(Some((
+                scale[0], scale[1], scale[2],
+            )))
+            .into()
+    }
+}
+
+/// A random value between `min` and `max`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Range {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Range {
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> f32 {
+        if self.max > self.min {
+            rng.gen_range(self.min, self.max)
+        } else {
+            self.min
+        }
+    }
+}
+
+/// An archetype of the entities spawned by the `GamePlayState`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpawnPrefab {
+    pub shape: MeshShape,
+    #[serde(default = "default_scale")]
+    pub scale: [f32; 3],
+    #[serde(default = "default_color")]
+    pub color: [f32; 4],
+    #[serde(default)]
+    pub metallic: f32,
+    #[serde(default = "default_roughness")]
+    pub roughness: f32,
+    /// Spawn position, randomized on each axis.
+    pub position: [Range; 3],
+    /// Vertical speed; negative values move down.
+    #[serde(default)]
+    pub motion: Option<Range>,
+    #[serde(default)]
+    pub tags: HashSet<String>,
+    /// Whether a mouse click can destroy it.
+    #[serde(default = "default_pickable")]
+    pub pickable: bool,
+    /// Points given when it's picked.
+    #[serde(default = "default_points")]
+    pub points: u32,
+}
+
+fn default_pickable() -> bool {
+    true
+}
+
+fn default_points() -> u32 {
+    1
+}
+
+fn default_scale() -> [f32; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+fn default_color() -> [f32; 4] {
+    [1.0, 1.0, 1.0, 1.0]
+}
+
+fn default_roughness() -> f32 {
+    1.0
+}
+
+impl SpawnPrefab {
+    /// The bounds of the generated mesh, used for picking.
+    pub fn pick_bounds(&self) -> PickBounds {
+        let [x, y, z] = self.scale;
+        match self.shape {
+            MeshShape::Sphere(..) | MeshShape::IcoSphere(_) => PickBounds::Sphere {
+                radius: x.max(y).max(z),
+            },
+            MeshShape::Cube | MeshShape::Cylinder(_) | MeshShape::Cone(_) => PickBounds::Box {
+                half_extents: Vector3::new(x, y, z),
+            },
+        }
+    }
+
+    pub fn sample_position<R: Rng>(&self, rng: &mut R) -> Vector3<f32> {
+        Vector3::new(
+            self.position[0].sample(rng),
+            self.position[1].sample(rng),
+            self.position[2].sample(rng),
+        )
+    }
+}
+
+/// The name of the prefab that spawned this entity, and its tags.
+pub struct PrefabInstance {
+    pub prefab: String,
+    pub tags: HashSet<String>,
+}
+
+impl PrefabInstance {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+}
+
+impl Component for PrefabInstance {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_stay_in_the_range() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let range = Range { min: -3.0, max: 2.0 };
+        for _ in 0..1000 {
+            let value = range.sample(&mut rng);
+            assert!(value >= range.min && value < range.max);
+        }
+    }
+
+    #[test]
+    fn empty_range_gives_its_min() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        assert_eq!(Range { min: 4.0, max: 4.0 }.sample(&mut rng), 4.0);
+        assert_eq!(Range { min: 4.0, max: 1.0 }.sample(&mut rng), 4.0);
+    }
+}
@@ -0,0 +1,159 @@
+use crate::game_state::Motion;
+
+use amethyst::{
+    core::{Time, Transform},
+    ecs::{Entities, Join, ReadExpect, ReadStorage, System, WriteExpect},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// The rules of the game, loaded from `configs/rules.ron`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RulesConfig {
+    pub lives: u32,
+    /// Objects moving down escape below `-goal_distance`, the ones moving up
+    /// escape above `goal_distance`.
+    pub goal_distance: f32,
+    /// Seconds between two difficulty levels.
+    pub level_duration: f32,
+    pub max_level: u32,
+    /// The spawn rate is multiplied by this at each level.
+    pub spawn_rate_step: f32,
+    /// The speed of the new objects is multiplied by this at each level.
+    pub speed_step: f32,
+}
+
+impl Default for RulesConfig {
+    fn default() -> Self {
+        RulesConfig {
+            lives: 3,
+            goal_distance: 12.0,
+            level_duration: 15.0,
+            max_level: 10,
+            spawn_rate_step: 1.15,
+            speed_step: 1.1,
+        }
+    }
+}
+
+/// The state of the current game.
+pub struct GameRules {
+    pub config: RulesConfig,
+    pub lives: u32,
+    pub escaped: u32,
+    /// Seconds played.
+    pub elapsed: f32,
+    /// Difficulty level, starting from `0`.
+    pub level: u32,
+    /// Cleared at game over, it stops the difficulty, the lives and the `PickingSystem`.
+    pub playing: bool,
+}
+
+impl GameRules {
+    pub fn new(config: RulesConfig) -> Self {
+        GameRules {
+            lives: config.lives,
+            config,
+            escaped: 0,
+            elapsed: 0.0,
+            level: 0,
+            playing: true,
+        }
+    }
+
+    pub fn spawn_rate_multiplier(&self) -> f32 {
+        self.config.spawn_rate_step.powi(self.level as i32)
+    }
+
+    pub fn speed_multiplier(&self) -> f32 {
+        self.config.speed_step.powi(self.level as i32)
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.lives == 0
+    }
+}
+
+/// Raises the difficulty over time, and takes a life for each object that
+/// crosses its goal plane.
+#[derive(Debug, Default)]
+pub struct RulesSystem;
+
+impl<'s> System<'s> for RulesSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadExpect<'s, Time>,
+        Option<WriteExpect<'s, GameRules>>,
+        ReadStorage<'s, Motion>,
+        ReadStorage<'s, Transform>,
+    );
+
+    fn run(&mut self, (entities, time, rules, motions, transforms): Self::SystemData) {
+        let mut rules = match rules {
+            Some(rules) => rules,
+            None => return,
+        };
+        if rules.playing {
+            rules.elapsed += time.delta_seconds();
+            let level = (rules.elapsed / rules.config.level_duration.max(std::f32::EPSILON)) as u32;
+            let level = level.min(rules.config.max_level);
+            if level != rules.level {
+                rules.level = level;
+                log::info!("Difficulty level {}", level);
+            }
+        }
+
+        let goal = rules.config.goal_distance;
+        for (entity, motion, transform) in (&*entities, &motions, &transforms).join() {
+            let y: f32 = transform.translation().y.into();
+            let escaped = (motion.speed < 0.0 && y < -goal) || (motion.speed > 0.0 && y > goal);
+            if !escaped {
+                continue;
+            }
+            // The objects that escape after the last life are just removed.
+            if rules.playing && !rules.is_game_over() {
+                rules.escaped += 1;
+                rules.lives -= 1;
+                log::info!("An object escaped, lives: {}", rules.lives);
+            }
+            entities.delete(entity).unwrap();
+        }
+
+        if rules.is_game_over() {
+            rules.playing = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multipliers_grow_with_the_level() {
+        let mut rules = GameRules::new(RulesConfig {
+            spawn_rate_step: 2.0,
+            speed_step: 1.5,
+            ..Default::default()
+        });
+        assert_eq!(rules.spawn_rate_multiplier(), 1.0);
+        assert_eq!(rules.speed_multiplier(), 1.0);
+
+        rules.level = 3;
+        assert_eq!(rules.spawn_rate_multiplier(), 8.0);
+        assert_eq!(rules.speed_multiplier(), 3.375);
+    }
+
+    #[test]
+    fn the_game_is_over_without_lives() {
+        let mut rules = GameRules::new(RulesConfig {
+            lives: 1,
+            ..Default::default()
+        });
+        assert!(!rules.is_game_over());
+
+        rules.lives -= 1;
+        assert!(rules.is_game_over());
+    }
+}
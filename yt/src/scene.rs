@@ -0,0 +1,78 @@
+use crate::{lighting::LightingDesc, prefab::SpawnPrefab};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+
+/// The content of `configs/scene.ron`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SceneDesc {
+    /// Without it, the scene is lit by a single directional light.
+    #[serde(default)]
+    pub lighting: LightingDesc,
+    #[serde(default)]
+    pub prefabs: HashMap<String, SpawnPrefab>,
+    /// Prefabs spawned by the `GamePlayState`, in turn.
+    #[serde(default)]
+    pub spawn_sequence: Vec<String>,
+    /// Seconds between two spawns.
+    #[serde(default = "default_spawn_interval")]
+    pub spawn_interval: f32,
+}
+
+fn default_spawn_interval() -> f32 {
+    1.0
+}
+
+impl Default for SceneDesc {
+    fn default() -> Self {
+        SceneDesc {
+            lighting: LightingDesc::default(),
+            prefabs: HashMap::new(),
+            spawn_sequence: Vec::new(),
+            spawn_interval: default_spawn_interval(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scene_file_round_trips() {
+        let data = std::fs::read_to_string("configs/scene.ron").unwrap();
+        let scene: SceneDesc = ron::de::from_str(&data).unwrap();
+        let data = ron::ser::to_string(&scene).unwrap();
+        let reloaded: SceneDesc = ron::de::from_str(&data).unwrap();
+
+        let mut prefabs: Vec<&String> = scene.prefabs.keys().collect();
+        let mut reloaded_prefabs: Vec<&String> = reloaded.prefabs.keys().collect();
+        prefabs.sort();
+        reloaded_prefabs.sort();
+        assert_eq!(prefabs, reloaded_prefabs);
+        assert_eq!(scene.spawn_sequence, reloaded.spawn_sequence);
+        assert_eq!(scene.spawn_interval, reloaded.spawn_interval);
+        assert_eq!(scene.lighting.lights.len(), reloaded.lighting.lights.len());
+
+        for (name, prefab) in scene.prefabs.iter() {
+            let reloaded = &reloaded.prefabs[name];
+            assert_eq!(format!("{:?}", prefab.shape), format!("{:?}", reloaded.shape));
+            assert_eq!(prefab.scale, reloaded.scale);
+            assert_eq!(prefab.color, reloaded.color);
+            assert_eq!(format!("{:?}", prefab.position), format!("{:?}", reloaded.position));
+            assert_eq!(format!("{:?}", prefab.motion), format!("{:?}", reloaded.motion));
+            assert_eq!(prefab.tags, reloaded.tags);
+            assert_eq!(prefab.pickable, reloaded.pickable);
+            assert_eq!(prefab.points, reloaded.points);
+        }
+    }
+
+    #[test]
+    fn empty_scene_uses_the_defaults() {
+        let scene: SceneDesc = ron::de::from_str("()").unwrap();
+        assert!(scene.prefabs.is_empty());
+        assert_eq!(scene.spawn_interval, default_spawn_interval());
+        assert_eq!(scene.lighting.lights.len(), 1);
+    }
+}